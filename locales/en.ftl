@@ -0,0 +1,297 @@
+# English messages, this is the fallback for messages that are missing in
+# other languages.
+
+about-description = Inspect the tabs in Firefox session files and export them as links.
+about-version = Version { $version }
+all-containers = All containers
+auto-increment = Add number to file name if it already exists
+browse = Browse
+browser-profiles = Browser Profiles:
+clean-urls = Clean URLs
+clear-log = Clear
+clear-selection = Clear selection
+clipboard-format-hover = Format of the links that are copied to the clipboard
+clipboard-html = HTML
+clipboard-markdown = Markdown
+clipboard-match-output = Same as output
+clipboard-plain-text = Plain text
+closed-tabs-from = Closed tabs from { $name }
+collapsed = (collapsed)
+container-id = Container { $id }
+copy-all-urls = Copy all URLs
+copy-link = Copy link
+copy-links = Copy links to clipboard
+copy-log = Copy log
+copy-log-hover = Copy all events as text, for example to include in a bug report
+copy-markdown-link = Copy as Markdown link
+copy-matching-tabs = Copy matching tabs
+copy-selected-tabs-count = Copy { $count ->
+        [one] the selected tab
+       *[other] { $count } selected tabs
+    }
+copy-title = Copy title
+copy-url = Copy URL
+create-folder = Create folder if it doesn't exist
+current-page-hover = The page that the tab currently shows
+delete = Delete
+double-click-rename = Double-click to rename
+error-create-file = failed to create new file at "{ $path }": { $error }
+error-create-folder = failed to create folder at "{ $path }": { $error }
+error-decompress = failed to decompress data: { $error }
+error-invalid-pattern = invalid pattern "{ $pattern }": { $error }
+error-modify-session = failed to modify session before export: { $error }
+error-no-chromium-session = could not find latest Chromium session file
+error-open-file = failed to open file at { $path }: { $error }
+error-parse-json = failed to parse sessionstore JSON data: { $error }
+error-read-file = failed to read file data from { $path }: { $error }
+error-statistics-unavailable = statistics are only available for Firefox sessions
+exclude-disabled = Only tabs from Firefox sessions can be excluded
+exclude-domains = Domains:
+exclude-domains-hover = One domain per line, subdomains are also excluded
+exclude-from-export = Exclude from export
+exclude-patterns = Patterns:
+exclude-patterns-hover = One pattern per line that must match the whole URL. Use * and ? as wildcards or start the line with "regex:" to write a regular expression.
+exclude-rules = Exclude rules
+exclude-rules-enabled = Exclude tabs that match these rules
+exclude-schemes = Schemes:
+exclude-schemes-hover = URL schemes separated by spaces or commas
+excluded-by-rules = { $count ->
+        [one] One tab
+       *[other] { $count } tabs
+    } excluded by rules
+excluded-by-rules-hover = Change the exclude rules to include them
+group-by = Group by:
+grouping-container = Container
+grouping-domain = Domain
+grouping-window = Window
+heading-closed-containers = Containers in Closed Windows
+heading-closed-domains = Domains in Closed Windows
+heading-closed-windows = Closed Windows
+heading-open-containers = Containers in Open Windows
+heading-open-domains = Domains in Open Windows
+heading-open-windows = Open Windows
+history-all-urls = All URLs visited in the tab
+history-current-page = Current page only
+history-full = Full back/forward history
+history-page-count = { $count ->
+        [one] One page
+       *[other] { $count } pages
+    }
+include-in-export = Include in export
+input-path = Path to sessionstore file:
+key-escape = Escape
+key-shift-space = Shift+Space
+key-space = Space
+key-up-down = Up / Down
+keyboard-shortcuts = Keyboard shortcuts
+load-new-data = Load new data
+loaded-from = Current data was loaded from:
+log-level-error = Error
+log-level-info = Info
+log-level-progress = Progress
+menu-about = About
+menu-closed-windows = Closed windows
+menu-command-palette = Command palette
+menu-copy-links = Copy links
+menu-copy-links-as = Copy links as
+menu-copy-selected-tabs = Copy selected tabs
+menu-edit = Edit
+menu-export = Export
+menu-file = File
+menu-find-profiles = Find Firefox profiles…
+menu-find-tabs = Find tabs
+menu-formatted-links = Formatted links
+menu-help = Help
+menu-invert-selection = Invert window selection
+menu-language = Language
+menu-open = Open…
+menu-open-recent = Open recent
+menu-quit = Quit
+menu-redo = Redo
+menu-reload = Reload
+menu-tab-list = Tab list
+menu-tab-tree = Tab tree
+menu-tab-tree-hover = Indent tabs below their parent tab when Sidebery or Tree Style Tab was used
+menu-undo = Undo
+menu-view = View
+menu-zoom = Zoom
+more-tabs = and { $count ->
+        [one] one more tab
+       *[other] { $count } more tabs
+    }
+no-container = No container
+no-links-changed = No links are changed
+no-presets = No saved presets
+no-windows-selected-warning = No windows selected, nothing will be exported
+open-link = Open link
+operation-clipboard = Clipboard
+operation-grouping = Grouping
+operation-load = Load
+operation-preview = Preview
+operation-save = Save
+operation-statistics = Statistics
+other-domain = Other
+output-format = Output format:
+overwrite-file = Overwrite file if it already exists
+palette-apply-preset = Apply preset: { $name }
+palette-copy-links-as = Copy links as: { $format }
+palette-group-by = Group by: { $grouping }
+palette-hint = Type to search for a command
+palette-no-matches = No matching commands
+palette-open-file = Open file
+palette-open-recent = Open recent: { $path }
+palette-output-format = Output format: { $format }
+palette-reload = Reload session file
+placeholder-date = Current date formatted as YYYY-MM-DD
+placeholder-ext = File extension for the selected output format
+placeholder-format = Name of the selected output format
+placeholder-profile = Name of the browser profile the session was loaded from
+placeholder-time = Current time formatted as HH-MM-SS
+placeholder-windows = Number of selected windows
+powered-by = Powered by
+powered-by-and = and
+powered-by-end = .
+preset-apply-hover = Select the windows and options from this preset
+preset-apply-on-load = Apply on load
+preset-apply-on-load-hover = Apply this preset whenever a session is loaded
+preset-name = Preset name
+presets = Selection presets
+preview-formatted = Formatted
+preview-source = Source
+preview-url-changes = Preview changes ({ $count ->
+        [one] one link
+       *[other] { $count } links
+    })
+regroup-disabled = Only Firefox sessions can be regrouped
+renamed-from = Renamed from "{ $name }", double-click to rename
+replace-preset = Replace preset
+save-links = Save links to file
+save-path = File path to write links to:
+save-path-expanded = Links will be written to:
+save-path-placeholders = The file path can contain these placeholders:
+save-selection = Save current selection
+search-hint = Filter tabs by title or URL
+search-label = Search:
+select-all = All
+select-all-closed = All closed
+select-all-closed-hover = Select only the closed windows
+select-all-windows = Select all windows
+select-at-least-one = Select at least one window
+select-invert = Invert
+select-label = Select:
+select-no-windows = Select no windows
+select-none = None
+shortcut-cheat-sheet = Show keyboard shortcuts
+shortcut-command-palette = Search for a command
+shortcut-escape = Stop moving in the window list
+shortcut-move = Move between windows
+shortcut-open-file = Browse for a session file
+shortcut-redo = Redo the latest undone change
+shortcut-reload = Load the session file again
+shortcut-search = Search tabs
+shortcut-select-range = Select all windows up to the last selected one
+shortcut-toggle = Select or deselect the window
+shortcut-undo = Undo the latest change to the selection, exclusions, window names or export options
+shortcut-window-list = In the window list, after clicking it:
+shortcut-zoom-in = Zoom in
+shortcut-zoom-out = Zoom out
+shortcut-zoom-reset = Reset zoom
+show-containers = Show containers in links
+show-containers-hover = Prefix the title of tabs in a container with its name
+show-history = Show history ({ $count ->
+        [one] one page
+       *[other] { $count } pages
+    })
+sort-tabs = Sort tabs:
+sort-tabs-disabled = Only tabs from Firefox sessions can be sorted
+sort-tabs-hover = Sorting tabs ignores tree structure from tab tree extensions
+sort-windows = Sort windows:
+sort-windows-disabled = Only windows from Firefox sessions can be sorted
+statistics = Statistics
+statistics-closed-window = { $name } (closed)
+statistics-containers = Containers
+statistics-depth = Depth { $depth }
+statistics-discarded-tabs = Discarded tabs
+statistics-hidden-tabs = Hidden tabs
+statistics-oldest-tabs = Oldest tabs
+statistics-pinned-tabs = Pinned tabs
+statistics-placeholder = Load a Firefox session to see statistics about its tabs.
+statistics-tabs-per-window = Tabs per window
+statistics-top-domains = Top domains
+statistics-tree-depth = Tree depth
+statistics-windows = Windows
+status-clipboard-failed = Failed to generate links for clipboard: { $error }
+status-clipboard-write-failed = Failed to write to clipboard: { $error }
+status-copied-link = Copied link to clipboard
+status-copied-links = Copied links to clipboard
+status-copied-log = Copied status log to clipboard
+status-copied-markdown-link = Copied Markdown link to clipboard
+status-copied-matching-tabs = Copied { $count ->
+        [one] the matching tab
+       *[other] { $count } matching tabs
+    } to clipboard
+status-copied-selected-tabs = Copied { $count ->
+        [one] the selected tab
+       *[other] { $count } selected tabs
+    } to clipboard
+status-copied-title = Copied title to clipboard
+status-copied-url = Copied URL to clipboard
+status-copied-urls = Copied { $count ->
+        [one] one URL
+       *[other] { $count } URLs
+    } to clipboard
+status-decompress-failed = Failed to decompress data: { $error }
+status-decompressing = Decompressing data
+status-generating-clipboard = Generating links for clipboard
+status-generating-preview = Generating preview
+status-grouping = Grouping tabs
+status-grouping-failed = Failed to group tabs: { $error }
+status-label = Status:
+status-list-chromium-windows-failed = Failed to list windows in Chromium session: { $error }
+status-list-windows-failed = Failed to list windows in session: { $error }
+status-loaded = Successfully loaded session data
+status-log = Status log
+status-log-empty = Nothing has happened yet
+status-log-errors = Status log ({ $count ->
+        [one] one error
+       *[other] { $count } errors
+    })
+status-no-windows = No windows selected
+status-parse-failed = Failed to parse session data: { $error }
+status-parsing = Parsing session data
+status-preset-windows-missing = None of the windows in the preset "{ $name }" exist in this session
+status-preview-failed = Failed to generate preview: { $error }
+status-read-failed = Failed to read file: { $error }
+status-reading = Reading input file
+status-save-failed = Failed to save links to file: { $error }
+status-saved = Successfully saved links to "{ $path }"
+status-saving = Saving links to file
+status-statistics-failed = Failed to calculate statistics: { $error }
+strip-tracking = Remove tracking parameters
+strip-tracking-hover = Remove utm_*, fbclid, gclid and similar query parameters
+summary-active-tab = Active tab
+summary-last-active = Last active
+summary-pinned = Pinned
+tab-closed-at = Closed { $time }
+tab-history-hover = Which pages from each tab's history are exported
+tab-history-label = Tab history:
+tab-history-title = Tab history
+tab-list-hint = Click a tab to open it, Ctrl+click or Shift+click to select tabs
+tab-matches-rule = The tab matches an exclude rule
+tab-sort-domain = Domain
+tab-sort-last-accessed = Last accessed
+tab-sort-position = Tab position
+tab-sort-title = Title
+tabs = Tabs
+tabs-as-links = Tabs as links:
+ungrouped-tabs = Ungrouped tabs
+unwrap-reader = Unwrap reader view links
+unwrap-reader-hover = Link to the page instead of its about:reader URL
+unwrap-suspended = Unwrap suspended tabs
+unwrap-suspended-hover = Link to the original page instead of the page of a tab suspender extension
+window-number = Window { $number }
+window-sort-last-activity = Last activity
+window-sort-name = Name
+window-sort-session = Session order
+window-sort-tab-count = Tab count
+wizard = Wizard
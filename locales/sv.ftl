@@ -0,0 +1,296 @@
+# Svenska (Swedish) messages.
+
+about-description = Granska flikarna i Firefox sessionsfiler och exportera dem som länkar.
+about-version = Version { $version }
+all-containers = Alla containrar
+auto-increment = Lägg till ett nummer i filnamnet om filen redan finns
+browse = Bläddra
+browser-profiles = Webbläsarprofiler:
+clean-urls = Rensa URL:er
+clear-log = Rensa
+clear-selection = Rensa markering
+clipboard-format-hover = Format på länkarna som kopieras till urklipp
+clipboard-html = HTML
+clipboard-markdown = Markdown
+clipboard-match-output = Samma som utdata
+clipboard-plain-text = Oformaterad text
+closed-tabs-from = Stängda flikar från { $name }
+collapsed = (hopfälld)
+container-id = Container { $id }
+copy-all-urls = Kopiera alla URL:er
+copy-link = Kopiera länk
+copy-links = Kopiera länkar till urklipp
+copy-log = Kopiera logg
+copy-log-hover = Kopiera alla händelser som text, till exempel för att bifoga i en felrapport
+copy-markdown-link = Kopiera som Markdown-länk
+copy-matching-tabs = Kopiera matchande flikar
+copy-selected-tabs-count = Kopiera { $count ->
+        [one] den markerade fliken
+       *[other] { $count } markerade flikar
+    }
+copy-title = Kopiera titel
+copy-url = Kopiera URL
+create-folder = Skapa mappen om den inte finns
+current-page-hover = Sidan som fliken visar just nu
+delete = Ta bort
+double-click-rename = Dubbelklicka för att byta namn
+error-create-file = det gick inte att skapa filen "{ $path }": { $error }
+error-create-folder = det gick inte att skapa mappen "{ $path }": { $error }
+error-decompress = det gick inte att packa upp data: { $error }
+error-invalid-pattern = ogiltigt mönster "{ $pattern }": { $error }
+error-modify-session = det gick inte att ändra sessionen före export: { $error }
+error-no-chromium-session = hittade ingen senaste Chromium-sessionsfil
+error-open-file = det gick inte att öppna filen { $path }: { $error }
+error-parse-json = det gick inte att tolka sessionens JSON-data: { $error }
+error-read-file = det gick inte att läsa data från { $path }: { $error }
+error-statistics-unavailable = statistik finns bara för Firefox-sessioner
+exclude-disabled = Endast flikar från Firefox-sessioner kan uteslutas
+exclude-domains = Domäner:
+exclude-domains-hover = En domän per rad, underdomäner utesluts också
+exclude-from-export = Uteslut från export
+exclude-patterns = Mönster:
+exclude-patterns-hover = Ett mönster per rad som måste matcha hela URL:en. Använd * och ? som jokertecken eller börja raden med "regex:" för att skriva ett reguljärt uttryck.
+exclude-rules = Uteslutningsregler
+exclude-rules-enabled = Uteslut flikar som matchar dessa regler
+exclude-schemes = Scheman:
+exclude-schemes-hover = URL-scheman åtskilda med mellanslag eller kommatecken
+excluded-by-rules = { $count ->
+        [one] En flik utesluten
+       *[other] { $count } flikar uteslutna
+    } av regler
+excluded-by-rules-hover = Ändra uteslutningsreglerna för att ta med dem
+group-by = Gruppera efter:
+grouping-container = Container
+grouping-domain = Domän
+grouping-window = Fönster
+heading-closed-containers = Containrar i stängda fönster
+heading-closed-domains = Domäner i stängda fönster
+heading-closed-windows = Stängda fönster
+heading-open-containers = Containrar i öppna fönster
+heading-open-domains = Domäner i öppna fönster
+heading-open-windows = Öppna fönster
+history-all-urls = Alla URL:er som besökts i fliken
+history-current-page = Endast nuvarande sida
+history-full = Hela bakåt/framåt-historiken
+history-page-count = { $count ->
+        [one] En sida
+       *[other] { $count } sidor
+    }
+include-in-export = Ta med i export
+input-path = Sökväg till sessionsfil:
+key-escape = Esc
+key-shift-space = Skift+Blanksteg
+key-space = Blanksteg
+key-up-down = Upp / Ned
+keyboard-shortcuts = Kortkommandon
+load-new-data = Läs in ny data
+loaded-from = Nuvarande data lästes in från:
+log-level-error = Fel
+log-level-info = Info
+log-level-progress = Pågår
+menu-about = Om
+menu-closed-windows = Stängda fönster
+menu-command-palette = Kommandopalett
+menu-copy-links = Kopiera länkar
+menu-copy-links-as = Kopiera länkar som
+menu-copy-selected-tabs = Kopiera markerade flikar
+menu-edit = Redigera
+menu-export = Exportera
+menu-file = Arkiv
+menu-find-profiles = Hitta Firefox-profiler…
+menu-find-tabs = Sök flikar
+menu-formatted-links = Formaterade länkar
+menu-help = Hjälp
+menu-invert-selection = Invertera fönstermarkeringen
+menu-language = Språk
+menu-open = Öppna…
+menu-open-recent = Öppna senaste
+menu-quit = Avsluta
+menu-redo = Gör om
+menu-reload = Läs in igen
+menu-tab-list = Fliklista
+menu-tab-tree = Flikträd
+menu-tab-tree-hover = Indentera flikar under sin överordnade flik när Sidebery eller Tree Style Tab användes
+menu-undo = Ångra
+menu-view = Visa
+menu-zoom = Zoom
+more-tabs = och { $count ->
+        [one] en flik till
+       *[other] { $count } flikar till
+    }
+no-container = Ingen container
+no-links-changed = Inga länkar ändras
+no-presets = Inga sparade markeringar
+no-windows-selected-warning = Inga fönster är markerade, inget kommer att exporteras
+open-link = Öppna länk
+operation-clipboard = Urklipp
+operation-grouping = Gruppering
+operation-load = Inläsning
+operation-preview = Förhandsvisning
+operation-save = Spara
+operation-statistics = Statistik
+other-domain = Övriga
+output-format = Utdataformat:
+overwrite-file = Skriv över filen om den redan finns
+palette-apply-preset = Använd sparad markering: { $name }
+palette-copy-links-as = Kopiera länkar som: { $format }
+palette-group-by = Gruppera efter: { $grouping }
+palette-hint = Skriv för att söka efter ett kommando
+palette-no-matches = Inga matchande kommandon
+palette-open-file = Öppna fil
+palette-open-recent = Öppna senaste: { $path }
+palette-output-format = Utdataformat: { $format }
+palette-reload = Läs in sessionsfilen igen
+placeholder-date = Dagens datum i formatet ÅÅÅÅ-MM-DD
+placeholder-ext = Filändelsen för det valda utdataformatet
+placeholder-format = Namnet på det valda utdataformatet
+placeholder-profile = Namnet på webbläsarprofilen som sessionen lästes in från
+placeholder-time = Aktuell tid i formatet TT-MM-SS
+placeholder-windows = Antal markerade fönster
+powered-by = Drivs av
+powered-by-and = och
+powered-by-end = .
+preset-apply-hover = Markera fönstren och alternativen från den här sparade markeringen
+preset-apply-on-load = Använd vid inläsning
+preset-apply-on-load-hover = Använd den här sparade markeringen varje gång en session läses in
+preset-name = Namn
+presets = Sparade markeringar
+preview-formatted = Formaterad
+preview-source = Källkod
+preview-url-changes = Förhandsvisa ändringar ({ $count ->
+        [one] en länk
+       *[other] { $count } länkar
+    })
+regroup-disabled = Endast Firefox-sessioner kan grupperas om
+renamed-from = Bytte namn från "{ $name }", dubbelklicka för att byta namn
+replace-preset = Ersätt sparad markering
+save-links = Spara länkar till fil
+save-path = Sökväg att skriva länkar till:
+save-path-expanded = Länkar kommer att skrivas till:
+save-path-placeholders = Sökvägen kan innehålla dessa platshållare:
+save-selection = Spara nuvarande markering
+search-hint = Filtrera flikar efter titel eller URL
+search-label = Sök:
+select-all = Alla
+select-all-closed = Alla stängda
+select-all-closed-hover = Markera endast de stängda fönstren
+select-all-windows = Markera alla fönster
+select-at-least-one = Markera minst ett fönster
+select-invert = Invertera
+select-label = Markera:
+select-no-windows = Avmarkera alla fönster
+select-none = Inga
+shortcut-cheat-sheet = Visa kortkommandon
+shortcut-command-palette = Sök efter ett kommando
+shortcut-escape = Sluta flytta i fönsterlistan
+shortcut-move = Flytta mellan fönster
+shortcut-open-file = Bläddra efter en sessionsfil
+shortcut-redo = Gör om den senast ångrade ändringen
+shortcut-reload = Läs in sessionsfilen igen
+shortcut-search = Sök flikar
+shortcut-select-range = Markera alla fönster fram till det senast markerade
+shortcut-toggle = Markera eller avmarkera fönstret
+shortcut-undo = Ångra den senaste ändringen av markeringen, uteslutningarna, fönsternamnen eller exportalternativen
+shortcut-window-list = I fönsterlistan, efter att du klickat i den:
+shortcut-zoom-in = Zooma in
+shortcut-zoom-out = Zooma ut
+shortcut-zoom-reset = Återställ zoom
+show-containers = Visa containrar i länkar
+show-containers-hover = Lägg till containerns namn före titeln på flikar i en container
+show-history = Visa historik ({ $count ->
+        [one] en sida
+       *[other] { $count } sidor
+    })
+sort-tabs = Sortera flikar:
+sort-tabs-disabled = Endast flikar från Firefox-sessioner kan sorteras
+sort-tabs-hover = Sortering av flikar ignorerar trädstrukturen från tillägg för flikträd
+sort-windows = Sortera fönster:
+sort-windows-disabled = Endast fönster från Firefox-sessioner kan sorteras
+statistics = Statistik
+statistics-closed-window = { $name } (stängt)
+statistics-containers = Containrar
+statistics-depth = Djup { $depth }
+statistics-discarded-tabs = Urladdade flikar
+statistics-hidden-tabs = Dolda flikar
+statistics-oldest-tabs = Äldsta flikar
+statistics-pinned-tabs = Fästa flikar
+statistics-placeholder = Läs in en Firefox-session för att se statistik om dess flikar.
+statistics-tabs-per-window = Flikar per fönster
+statistics-top-domains = Vanligaste domäner
+statistics-tree-depth = Träddjup
+statistics-windows = Fönster
+status-clipboard-failed = Det gick inte att skapa länkar för urklipp: { $error }
+status-clipboard-write-failed = Det gick inte att skriva till urklipp: { $error }
+status-copied-link = Kopierade länken till urklipp
+status-copied-links = Kopierade länkarna till urklipp
+status-copied-log = Kopierade statusloggen till urklipp
+status-copied-markdown-link = Kopierade Markdown-länken till urklipp
+status-copied-matching-tabs = Kopierade { $count ->
+        [one] den matchande fliken
+       *[other] { $count } matchande flikar
+    } till urklipp
+status-copied-selected-tabs = Kopierade { $count ->
+        [one] den markerade fliken
+       *[other] { $count } markerade flikar
+    } till urklipp
+status-copied-title = Kopierade titeln till urklipp
+status-copied-url = Kopierade URL:en till urklipp
+status-copied-urls = Kopierade { $count ->
+        [one] en URL
+       *[other] { $count } URL:er
+    } till urklipp
+status-decompress-failed = Det gick inte att packa upp data: { $error }
+status-decompressing = Packar upp data
+status-generating-clipboard = Skapar länkar för urklipp
+status-generating-preview = Skapar förhandsvisning
+status-grouping = Grupperar flikar
+status-grouping-failed = Det gick inte att gruppera flikar: { $error }
+status-label = Status:
+status-list-chromium-windows-failed = Det gick inte att lista fönstren i Chromium-sessionen: { $error }
+status-list-windows-failed = Det gick inte att lista fönstren i sessionen: { $error }
+status-loaded = Sessionsdata har lästs in
+status-log = Statuslogg
+status-log-empty = Inget har hänt ännu
+status-log-errors = Statuslogg ({ $count ->
+        [one] ett fel
+       *[other] { $count } fel
+    })
+status-no-windows = Inga fönster är markerade
+status-parse-failed = Det gick inte att tolka sessionsdata: { $error }
+status-parsing = Tolkar sessionsdata
+status-preset-windows-missing = Inget av fönstren i förinställningen "{ $name }" finns i den här sessionen
+status-preview-failed = Det gick inte att skapa förhandsvisning: { $error }
+status-read-failed = Det gick inte att läsa filen: { $error }
+status-reading = Läser indatafil
+status-save-failed = Det gick inte att spara länkar till fil: { $error }
+status-saved = Länkarna sparades i "{ $path }"
+status-saving = Sparar länkar till fil
+status-statistics-failed = Det gick inte att beräkna statistik: { $error }
+strip-tracking = Ta bort spårningsparametrar
+strip-tracking-hover = Ta bort utm_*, fbclid, gclid och liknande frågeparametrar
+summary-active-tab = Aktiv flik
+summary-last-active = Senast aktiv
+summary-pinned = Fästa
+tab-closed-at = Stängd { $time }
+tab-history-hover = Vilka sidor från varje fliks historik som exporteras
+tab-history-label = Flikhistorik:
+tab-history-title = Flikhistorik
+tab-list-hint = Klicka på en flik för att öppna den, Ctrl+klicka eller Skift+klicka för att markera flikar
+tab-matches-rule = Fliken matchar en uteslutningsregel
+tab-sort-domain = Domän
+tab-sort-last-accessed = Senast använd
+tab-sort-position = Flikens position
+tab-sort-title = Titel
+tabs = Flikar
+tabs-as-links = Flikar som länkar:
+ungrouped-tabs = Ogrupperade flikar
+unwrap-reader = Packa upp länkar från läsvyn
+unwrap-reader-hover = Länka till sidan i stället för dess about:reader-URL
+unwrap-suspended = Packa upp vilande flikar
+unwrap-suspended-hover = Länka till den ursprungliga sidan i stället för sidan från ett tillägg som söver flikar
+window-number = Fönster { $number }
+window-sort-last-activity = Senaste aktivitet
+window-sort-name = Namn
+window-sort-session = Sessionens ordning
+window-sort-tab-count = Antal flikar
+wizard = Guide
@@ -1,28 +1,127 @@
 use core::f32;
-use std::path::PathBuf;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use egui::Widget;
 
 use crate::{
     background::EguiBackgroundWork,
+    clipboard::ClipboardContent,
+    command_palette::CommandPalette,
+    containers,
     egui_utils::{FakeMutable, ObservableMutable},
-    host::{self, WebSendable},
+    exclude_rules::ExcludeRules,
+    host::{self, GenerateOptions, WebSendable},
+    i18n::{self, tr},
+    presets::SelectionPreset,
+    preview,
+    session::{self, NativeGroupId, TabId},
+    shortcuts,
+    statistics::{self, Statistics},
+    status_log::{Operation, StatusEvent, StatusLevel, StatusLog},
+    undo::{EditState, UndoHistory},
+    url_cleaning::{self, UrlChange},
 };
 
+/// How the generated links are shown to the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum PreviewMode {
+    /// List of tabs that can be interacted with.
+    Tabs,
+    /// Rendered version of the selected output format.
+    Formatted,
+    /// Source code of the selected output format.
+    Source,
+}
+
+/// A row in the list of tabs that is shown as a preview.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TabRow {
+    Window { open: bool, index: u32 },
+    Tab(TabId),
+}
+
+/// Range of the zoom setting in the View menu.
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 3.0;
+
+/// How many recently loaded session files are remembered.
+const RECENT_FILES: usize = 10;
+
 #[derive(Clone)]
 pub enum Command {
     SetInputPath(String, WebSendable<rfd::FileHandle>),
     UpdateLoadedData(host::FileInfo),
     ParsedTabGroups(host::AllTabGroups),
-    SetPreview(String),
-    ChangeTabGroupSelection {
-        open: bool,
-        index: u32,
+    SetPreview(preview::Preview),
+    SetStatistics(Arc<Statistics>),
+    /// A window was clicked in the side panel.
+    ClickTabGroup {
+        window: host::WindowKey,
+        modifiers: egui::Modifiers,
+    },
+    SetTabExcluded {
+        tab: TabId,
+        exclude: bool,
+    },
+    ChangeNativeGroupSelection {
+        group: NativeGroupId,
         select: bool,
     },
+    /// Give a window a custom name, `None` restores the session's name.
+    RenameWindow {
+        window: host::WindowKey,
+        name: Option<String>,
+    },
     SetSavePath(String),
-    SetStatus(String),
+    SetStatus(StatusEvent),
     SaveLinksToFile,
+    /// Ask the user for a session file to load.
+    BrowseInputFile,
+    /// Load the session file at this path.
+    OpenFile(String),
+    /// Load the current input file again.
+    LoadInputData,
+    CopyLinks,
+    /// Copy links once in a clipboard format other than the chosen one.
+    CopyLinksAs(host::ClipboardFormat),
+    /// Copy links to the tabs that are selected in the tab list.
+    CopySelectedTabs,
+    CopyStatusLog,
+    SetOutputFormat(host::FormatInfo),
+    SetTabGrouping(host::TabGrouping),
+    /// Select every window (`true`) or no windows (`false`).
+    SelectAllGroups(bool),
+    /// Select the windows that aren't selected and deselect the others.
+    InvertGroupSelection,
+    /// Select only the closed windows.
+    SelectClosedGroups,
+    /// Undo the latest change to the selection, exclusions, window names,
+    /// exclude rules or export options from a preset.
+    Undo,
+    Redo,
+    /// Apply the preset at this index in the list of saved presets.
+    ApplyPreset(usize),
+}
+
+impl Command {
+    /// Changes made by this command can be undone.
+    fn is_undoable(&self) -> bool {
+        matches!(
+            self,
+            Command::ClickTabGroup { .. }
+                | Command::SetTabExcluded { .. }
+                | Command::ChangeNativeGroupSelection { .. }
+                | Command::RenameWindow { .. }
+                | Command::SelectAllGroups(_)
+                | Command::InvertGroupSelection
+                | Command::SelectClosedGroups
+                | Command::ApplyPreset(_)
+        )
+    }
 }
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
@@ -46,14 +145,103 @@ pub struct FirefoxSessionDataApp {
     selected_tab_groups: host::GenerateOptions,
 
     #[serde(skip)]
-    preview: String,
+    preview: preview::Preview,
+    preview_mode: PreviewMode,
+    #[serde(skip)]
+    tab_rows: Vec<TabRow>,
+    /// Only show tabs whose title or URL contains this text.
+    #[serde(skip)]
+    tab_search: String,
+    /// Tabs that are selected in the tab list.
+    #[serde(skip)]
+    selected_tabs: BTreeSet<TabId>,
+    /// The tab that was last clicked, used when selecting a range of tabs.
+    #[serde(skip)]
+    tab_selection_anchor: Option<TabId>,
+    /// Only show tabs in the container with this id.
+    #[serde(skip)]
+    tab_container_filter: Option<u32>,
+    /// Tab whose back/forward history is shown in a window.
+    #[serde(skip)]
+    history_tab: Option<TabId>,
+    /// URLs that are changed by the selected URL cleaning, computed when
+    /// needed.
+    #[serde(skip)]
+    url_changes: Option<Vec<UrlChange>>,
+
+    exclude_rules: ExcludeRules,
+    /// Set if the exclude rules couldn't be parsed.
+    #[serde(skip)]
+    exclude_rules_error: Option<String>,
+
+    /// Custom window names for each session file, keyed by the file's path.
+    window_names: BTreeMap<String, BTreeMap<host::WindowKey, String>>,
+    /// The window whose name is being edited in the side panel.
+    #[serde(skip)]
+    renaming_window: Option<(host::WindowKey, String)>,
+    /// The window that was last clicked in the side panel, used when
+    /// selecting a range of windows.
+    #[serde(skip)]
+    group_selection_anchor: Option<host::WindowKey>,
+    /// The window that is highlighted when moving through the window list
+    /// with the arrow keys.
+    #[serde(skip)]
+    group_cursor: Option<host::WindowKey>,
+    /// Scroll the window list so that `group_cursor` is visible.
+    #[serde(skip)]
+    scroll_to_group_cursor: bool,
+    /// The window list was clicked and nothing else has been clicked since,
+    /// so the arrow keys and Space move through it.
+    #[serde(skip)]
+    window_list_focused: bool,
+    /// Give the tab search keyboard focus the next time it is shown.
+    #[serde(skip)]
+    focus_tab_search: bool,
+    #[serde(skip)]
+    show_shortcuts: bool,
+    #[serde(skip)]
+    show_about: bool,
+    /// List recently closed windows in the side panel.
+    show_closed_windows: bool,
+    #[serde(skip)]
+    command_palette: CommandPalette,
+    #[serde(skip)]
+    undo_history: UndoHistory,
+    /// The state from before the exclude rules' text started to change, it is
+    /// recorded as a single change once the text field loses focus.
+    #[serde(skip)]
+    exclude_rules_edit: Option<EditState>,
+    /// The last computed final save path together with the path and auto
+    /// increment option it was computed from, so that the file system isn't
+    /// checked every frame.
+    #[serde(skip)]
+    final_save_path: Option<(PathBuf, bool, PathBuf)>,
+    /// Session files that were loaded recently, most recent first.
+    recent_files: Vec<String>,
+
+    presets: Vec<SelectionPreset>,
+    #[serde(skip)]
+    new_preset_name: String,
+    /// Preset that should be applied once the tab groups have been loaded.
+    #[serde(skip)]
+    pending_preset: Option<SelectionPreset>,
+
+    clipboard_format: host::ClipboardFormat,
+
+    show_statistics: bool,
+    show_status_log: bool,
+    /// Zoom factor on top of the operating system's scale factor.
+    ui_zoom: f32,
+    language: i18n::Language,
+    #[serde(skip)]
+    statistics: Option<Arc<Statistics>>,
 
     save_path: String,
     #[serde(skip)] // <- TODO: we want to persist this
     output_options: host::OutputOptions,
 
     #[serde(skip)]
-    status: String,
+    status_log: StatusLog,
 
     #[serde(skip)]
     background: EguiBackgroundWork<Command>,
@@ -75,39 +263,75 @@ impl Default for FirefoxSessionDataApp {
                     host::TabGroup {
                         index: 0,
                         name: "Window 1".into(),
+                        tabs: None,
+                        window: None,
+                        summary: Default::default(),
                     },
                     host::TabGroup {
                         index: 1,
                         name: "Window 2".into(),
+                        tabs: None,
+                        window: None,
+                        summary: Default::default(),
                     },
                 ],
                 closed: vec![host::TabGroup {
                     index: 3,
                     name: "Closed window 1".into(),
+                    tabs: None,
+                    window: None,
+                    summary: Default::default(),
                 }],
             },
             #[cfg(not(debug_assertions))]
             tab_groups: Default::default(),
             selected_tab_groups: Default::default(),
 
-            preview: String::new(),
+            preview: Default::default(),
+            preview_mode: PreviewMode::Tabs,
+            tab_rows: Vec::new(),
+            tab_search: String::new(),
+            selected_tabs: BTreeSet::new(),
+            tab_selection_anchor: None,
+            tab_container_filter: None,
+            history_tab: None,
+            url_changes: None,
 
-            // TODO: more robust finding of downloads folder.
-            save_path: {
-                #[cfg(not(target_family = "wasm"))]
-                {
-                    std::env::var("USERPROFILE")
-                        .map(|home| home + r"\Downloads\firefox-links")
-                        .unwrap_or_default()
-                }
-                #[cfg(target_family = "wasm")]
-                {
-                    String::new()
-                }
-            },
+            exclude_rules: Default::default(),
+            exclude_rules_error: None,
+
+            window_names: BTreeMap::new(),
+            renaming_window: None,
+            group_selection_anchor: None,
+            group_cursor: None,
+            scroll_to_group_cursor: false,
+            window_list_focused: false,
+            focus_tab_search: false,
+            show_shortcuts: false,
+            show_about: false,
+            show_closed_windows: true,
+            command_palette: CommandPalette::default(),
+            undo_history: UndoHistory::default(),
+            exclude_rules_edit: None,
+            final_save_path: None,
+            recent_files: Vec::new(),
+
+            presets: Vec::new(),
+            new_preset_name: String::new(),
+            pending_preset: None,
+
+            clipboard_format: Default::default(),
+
+            show_statistics: false,
+            show_status_log: false,
+            ui_zoom: 1.0,
+            language: i18n::Language::default(),
+            statistics: None,
+
+            save_path: crate::save_path::default_save_path(),
             output_options: Default::default(),
 
-            status: String::new(),
+            status_log: StatusLog::default(),
 
             background: EguiBackgroundWork::default(),
         }
@@ -120,18 +344,51 @@ impl FirefoxSessionDataApp {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
-        cc.egui_ctx.set_pixels_per_point(1.3);
-
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
-        if let Some(storage) = cc.storage {
+        let app: Self = if let Some(storage) = cc.storage {
             eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default()
         } else {
             Default::default()
-        }
+        };
+        // The zoom is applied on top of the operating system's scale factor:
+        cc.egui_ctx
+            .set_zoom_factor(app.ui_zoom.clamp(MIN_ZOOM, MAX_ZOOM));
+        i18n::set_language(app.language);
+        app
     }
 
     pub fn handle_command(&mut self, ctx: &egui::Context, command: Command) {
+        if !command.is_undoable() {
+            self.run_command(ctx, command);
+            return;
+        }
+        let before = self.edit_state();
+        let grouping = self.selected_tab_groups.grouping;
+        self.run_command(ctx, command);
+        // Changing the grouping forgets all changes, since the indexes in them
+        // refer to the previous groups:
+        if grouping == self.selected_tab_groups.grouping {
+            self.undo_history.record(before, &self.edit_state());
+        }
+    }
+
+    fn edit_state(&self) -> EditState {
+        EditState::of(&self.selected_tab_groups, &self.exclude_rules)
+    }
+
+    /// Record a change that a widget made directly to the export options, so
+    /// that it can be undone like the changes that commands make.
+    fn record_options_change(&mut self, before: impl FnOnce(&mut host::GenerateOptions)) {
+        let mut options = self.selected_tab_groups.clone();
+        before(&mut options);
+        self.undo_history.record(
+            EditState::of(&options, &self.exclude_rules),
+            &self.edit_state(),
+        );
+    }
+
+    fn run_command(&mut self, ctx: &egui::Context, command: Command) {
         match command {
             Command::SetInputPath(v, handle) => {
                 self.input_path = v;
@@ -141,159 +398,2003 @@ impl FirefoxSessionDataApp {
                 self.loaded_data = Some(new_data);
             }
             Command::ParsedTabGroups(all_groups) => {
+                self.status_log.finish(Operation::Grouping);
                 self.tab_groups = all_groups;
-                self.regenerate_preview(ctx)
+                self.sort_tab_groups();
+                self.update_rule_excluded_tabs();
+                if let Some(preset) = self.pending_preset.take() {
+                    self.apply_preset(ctx, preset);
+                } else {
+                    self.regenerate_preview(ctx);
+                }
+                if self.show_statistics {
+                    self.compute_statistics(ctx);
+                }
             }
             Command::SetPreview(v) => {
-                self.status = "Successfully loaded session data".to_string();
+                self.status_log.finish(Operation::Preview);
                 self.preview = v;
             }
-            Command::ChangeTabGroupSelection {
-                index,
-                open,
-                select,
-            } => {
-                self.change_selected_tab_group(ctx, index, open, select);
+            Command::SetStatistics(v) => {
+                self.statistics = Some(v);
+            }
+            Command::ClickTabGroup { window, modifiers } => {
+                self.click_tab_group(ctx, window, modifiers);
+            }
+            Command::SetTabExcluded { tab, exclude } => {
+                let excluded_tabs = &mut self.selected_tab_groups.excluded_tabs;
+                let changed = if exclude {
+                    excluded_tabs.insert(tab)
+                } else {
+                    excluded_tabs.remove(&tab)
+                };
+                if changed {
+                    self.regenerate_preview(ctx);
+                }
+            }
+            Command::ChangeNativeGroupSelection { group, select } => {
+                self.change_selected_native_group(ctx, group, select);
+            }
+            Command::RenameWindow { window, name } => {
+                self.rename_window(ctx, window, name);
             }
             Command::SetSavePath(v) => {
                 self.save_path = v;
             }
             Command::SetStatus(v) => {
-                self.status = v;
+                if v.operation == Operation::Save {
+                    // A file might have been written:
+                    self.final_save_path = None;
+                }
+                self.status_log.push(v);
             }
             Command::SaveLinksToFile => {
                 let Some(data) = self.loaded_data.clone() else {
                     return;
                 };
-                let save_path = PathBuf::from(self.save_path.as_str());
+                let save_path = PathBuf::from(self.expanded_save_path());
                 let selected = self.selected_tab_groups.clone();
                 let output_options = self.output_options.clone();
 
-                self.status = "Saving links to file".to_string();
+                self.status_log
+                    .push(StatusEvent::progress(Operation::Save, tr!("status-saving")));
                 self.background.spawn(ctx, async move {
                     Some(
-                        if let Err(e) = data.save_links(save_path, selected, output_options).await {
-                            Command::SetStatus(format!("Failed to save links to file: {e}"))
-                        } else {
-                            Command::SetStatus("Successfully saved links to a file".to_owned())
+                        match data.save_links(save_path, selected, output_options).await {
+                            Err(e) => Command::SetStatus(StatusEvent::error(
+                                Operation::Save,
+                                tr!("status-save-failed", error = e),
+                            )),
+                            Ok(path) => Command::SetStatus(StatusEvent::info(
+                                Operation::Save,
+                                tr!("status-saved", path = path.display()),
+                            )),
                         },
                     )
                 });
-            }
-        }
+            }
+            Command::BrowseInputFile => self.browse_input_file(ctx, None),
+            Command::OpenFile(path) => {
+                self.input_path = path;
+                self.input_data = None;
+                self.load_input_data(ctx);
+            }
+            Command::LoadInputData => self.load_input_data(ctx),
+            Command::CopyLinks => {
+                self.copy_links_to_clipboard(
+                    ctx,
+                    self.selected_tab_groups.clone(),
+                    self.clipboard_format,
+                    tr!("status-copied-links"),
+                );
+            }
+            Command::CopyLinksAs(clipboard_format) => {
+                self.copy_links_to_clipboard(
+                    ctx,
+                    self.selected_tab_groups.clone(),
+                    clipboard_format,
+                    tr!("status-copied-links"),
+                );
+            }
+            Command::CopySelectedTabs => {
+                let selected = self.selected_tabs.clone();
+                self.copy_tabs_to_clipboard(
+                    ctx,
+                    &selected,
+                    tr!("status-copied-selected-tabs", count = selected.len()),
+                );
+            }
+            Command::CopyStatusLog => {
+                self.copy_to_clipboard(ctx, self.status_log.to_text(), tr!("status-copied-log"));
+            }
+            Command::SetOutputFormat(format) => {
+                if format != self.output_options.format {
+                    self.output_options.format = format;
+                    self.regenerate_preview(ctx);
+                }
+            }
+            Command::SetTabGrouping(grouping) => {
+                if grouping != self.selected_tab_groups.grouping {
+                    self.set_tab_grouping(ctx, grouping);
+                }
+            }
+            Command::SelectAllGroups(select) => {
+                let selected = if select {
+                    self.group_keys().into_iter().collect()
+                } else {
+                    BTreeSet::new()
+                };
+                self.set_selected_groups(ctx, selected);
+            }
+            Command::InvertGroupSelection => self.invert_group_selection(ctx),
+            Command::SelectClosedGroups => {
+                self.show_closed_windows = true;
+                let closed = self
+                    .group_keys()
+                    .into_iter()
+                    .filter(|window| !window.open)
+                    .collect();
+                self.set_selected_groups(ctx, closed);
+            }
+            Command::Undo => {
+                let current = self.edit_state();
+                if let Some(state) = self.undo_history.undo(current) {
+                    self.restore_edit_state(ctx, state);
+                }
+            }
+            Command::Redo => {
+                let current = self.edit_state();
+                if let Some(state) = self.undo_history.redo(current) {
+                    self.restore_edit_state(ctx, state);
+                }
+            }
+            Command::ApplyPreset(index) => {
+                if let Some(preset) = self.presets.get(index).cloned() {
+                    self.apply_preset(ctx, preset);
+                }
+            }
+        }
+    }
+
+    /// The menus at the top of the window.
+    fn menu_bar_ui(&mut self, ui: &mut egui::Ui, frame: &eframe::Frame) {
+        let is_web = cfg!(target_arch = "wasm32");
+        let can_export = self.loaded_data.is_some() && !self.selected_tab_groups.selects_nothing();
+        egui::MenuBar::new().ui(ui, |ui| {
+            ui.menu_button(tr!("menu-file"), |ui| {
+                if ui
+                    .add(shortcuts::button(
+                        ui,
+                        tr!("menu-open"),
+                        shortcuts::OPEN_FILE,
+                    ))
+                    .clicked()
+                {
+                    self.browse_input_file(ui, Some(frame));
+                }
+                if !is_web {
+                    ui.add_enabled_ui(!self.recent_files.is_empty(), |ui| {
+                        ui.menu_button(tr!("menu-open-recent"), |ui| {
+                            for path in self.recent_files.clone() {
+                                if ui.button(path.as_str()).clicked() {
+                                    self.handle_command(ui, Command::OpenFile(path));
+                                }
+                            }
+                        });
+                    });
+                    if ui.button(tr!("menu-find-profiles")).clicked() {
+                        self.wizard_state = Some(host::FirefoxProfileInfo::all_profiles());
+                    }
+                }
+                if ui
+                    .add_enabled(
+                        !self.input_path.is_empty(),
+                        shortcuts::button(ui, tr!("menu-reload"), shortcuts::RELOAD),
+                    )
+                    .clicked()
+                {
+                    self.load_input_data(ui);
+                }
+                // NOTE: no "File->Quit" menu item on web pages!
+                if !is_web {
+                    ui.separator();
+                    if ui.button(tr!("menu-quit")).clicked() {
+                        ui.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                }
+            });
+
+            ui.menu_button(tr!("menu-edit"), |ui| {
+                if ui
+                    .add_enabled(
+                        self.undo_history.can_undo(),
+                        shortcuts::button(ui, tr!("menu-undo"), shortcuts::UNDO),
+                    )
+                    .clicked()
+                {
+                    self.handle_command(ui, Command::Undo);
+                }
+                if ui
+                    .add_enabled(
+                        self.undo_history.can_redo(),
+                        shortcuts::button(ui, tr!("menu-redo"), shortcuts::REDO),
+                    )
+                    .clicked()
+                {
+                    self.handle_command(ui, Command::Redo);
+                }
+                ui.separator();
+                if ui.button(tr!("select-all-windows")).clicked() {
+                    self.handle_command(ui, Command::SelectAllGroups(true));
+                }
+                if ui.button(tr!("select-no-windows")).clicked() {
+                    self.handle_command(ui, Command::SelectAllGroups(false));
+                }
+                if ui.button(tr!("menu-invert-selection")).clicked() {
+                    self.handle_command(ui, Command::InvertGroupSelection);
+                }
+                ui.separator();
+                if ui
+                    .add_enabled(
+                        self.loaded_tabs().is_some(),
+                        shortcuts::button(ui, tr!("menu-find-tabs"), shortcuts::FOCUS_SEARCH),
+                    )
+                    .clicked()
+                {
+                    self.preview_mode = PreviewMode::Tabs;
+                    self.focus_tab_search = true;
+                }
+                if ui
+                    .add(shortcuts::button(
+                        ui,
+                        tr!("menu-command-palette"),
+                        shortcuts::COMMAND_PALETTE,
+                    ))
+                    .clicked()
+                {
+                    self.command_palette.open();
+                }
+            });
+
+            ui.menu_button(tr!("menu-view"), |ui| {
+                if ui
+                    .checkbox(&mut self.show_closed_windows, tr!("menu-closed-windows"))
+                    .changed()
+                {
+                    self.deselect_hidden_windows(ui);
+                }
+                if ui
+                    .checkbox(&mut self.selected_tab_groups.tab_tree, tr!("menu-tab-tree"))
+                    .on_hover_text(tr!("menu-tab-tree-hover"))
+                    .changed()
+                {
+                    self.record_options_change(|options| options.tab_tree = !options.tab_tree);
+                    self.regenerate_preview(ui);
+                }
+                if ui
+                    .checkbox(&mut self.show_statistics, tr!("statistics"))
+                    .changed()
+                    && self.show_statistics
+                    && self.statistics.is_none()
+                {
+                    self.compute_statistics(ui);
+                }
+                ui.checkbox(&mut self.show_status_log, tr!("status-log"));
+                ui.separator();
+                ui.add_enabled_ui(self.loaded_tabs().is_some(), |ui| {
+                    ui.radio_value(
+                        &mut self.preview_mode,
+                        PreviewMode::Tabs,
+                        tr!("menu-tab-list"),
+                    );
+                });
+                ui.add_enabled_ui(self.preview.syntax.can_render(), |ui| {
+                    ui.radio_value(
+                        &mut self.preview_mode,
+                        PreviewMode::Formatted,
+                        tr!("menu-formatted-links"),
+                    );
+                });
+                ui.radio_value(
+                    &mut self.preview_mode,
+                    PreviewMode::Source,
+                    tr!("preview-source"),
+                );
+                ui.separator();
+                let mut zoom = ui.zoom_factor();
+                if ui
+                    .add(
+                        egui::Slider::new(&mut zoom, MIN_ZOOM..=MAX_ZOOM)
+                            .text(tr!("menu-zoom"))
+                            .custom_formatter(|zoom, _| format!("{:.0}%", zoom * 100.0)),
+                    )
+                    .changed()
+                {
+                    ui.set_zoom_factor(zoom);
+                }
+                egui::gui_zoom::zoom_menu_buttons(ui);
+                ui.separator();
+                ui.menu_button(tr!("menu-language"), |ui| {
+                    for &language in i18n::Language::all() {
+                        if ui
+                            .radio_value(&mut self.language, language, language.as_str())
+                            .clicked()
+                        {
+                            i18n::set_language(language);
+                        }
+                    }
+                });
+            });
+
+            ui.menu_button(tr!("menu-export"), |ui| {
+                ui.label(tr!("output-format"));
+                for &format in host::FormatInfo::all() {
+                    if ui
+                        .radio(format == self.output_options.format, format.as_str())
+                        .on_hover_text(format.to_string())
+                        .clicked()
+                    {
+                        self.handle_command(ui, Command::SetOutputFormat(format));
+                    }
+                }
+                ui.separator();
+                if ui
+                    .add_enabled(
+                        can_export,
+                        shortcuts::button(ui, tr!("menu-copy-links"), shortcuts::COPY_LINKS),
+                    )
+                    .clicked()
+                {
+                    self.handle_command(ui, Command::CopyLinks);
+                }
+                ui.add_enabled_ui(can_export, |ui| {
+                    ui.menu_button(tr!("menu-copy-links-as"), |ui| {
+                        for &format in host::ClipboardFormat::all() {
+                            if ui.button(format.label()).clicked() {
+                                self.handle_command(ui, Command::CopyLinksAs(format));
+                            }
+                        }
+                    });
+                });
+                if ui
+                    .add_enabled(
+                        !self.selected_tabs.is_empty(),
+                        egui::Button::new(tr!("menu-copy-selected-tabs")),
+                    )
+                    .clicked()
+                {
+                    self.handle_command(ui, Command::CopySelectedTabs);
+                }
+                ui.separator();
+                if ui
+                    .add_enabled(
+                        can_export,
+                        shortcuts::button(ui, tr!("save-links"), shortcuts::SAVE),
+                    )
+                    .clicked()
+                {
+                    self.handle_command(ui, Command::SaveLinksToFile);
+                }
+            });
+
+            ui.menu_button(tr!("menu-help"), |ui| {
+                if ui
+                    .add(shortcuts::button(
+                        ui,
+                        tr!("keyboard-shortcuts"),
+                        shortcuts::CHEAT_SHEET,
+                    ))
+                    .clicked()
+                {
+                    self.show_shortcuts = true;
+                }
+                if ui.button(tr!("menu-about")).clicked() {
+                    self.show_about = true;
+                }
+            });
+            ui.add_space(16.0);
+
+            egui::widgets::global_theme_preference_buttons(ui);
+        });
+    }
+
+    /// Actions that are listed in the command palette.
+    fn palette_actions(&self) -> Vec<(String, Command)> {
+        let mut actions = vec![(tr!("palette-open-file"), Command::BrowseInputFile)];
+        if !self.input_path.is_empty() {
+            actions.push((tr!("palette-reload"), Command::LoadInputData));
+        }
+        actions.extend(self.recent_files.iter().map(|path| {
+            (
+                tr!("palette-open-recent", path = path),
+                Command::OpenFile(path.clone()),
+            )
+        }));
+        if self.loaded_data.is_some() && !self.selected_tab_groups.selects_nothing() {
+            actions.push((tr!("copy-links"), Command::CopyLinks));
+            actions.extend(host::ClipboardFormat::all().iter().map(|&format| {
+                (
+                    tr!("palette-copy-links-as", format = format.label()),
+                    Command::CopyLinksAs(format),
+                )
+            }));
+            actions.push((tr!("save-links"), Command::SaveLinksToFile));
+        }
+        if !self.selected_tabs.is_empty() {
+            actions.push((tr!("menu-copy-selected-tabs"), Command::CopySelectedTabs));
+        }
+        actions.push((tr!("copy-log"), Command::CopyStatusLog));
+        actions.extend(host::FormatInfo::all().iter().map(|&format| {
+            (
+                tr!("palette-output-format", format = format.as_str()),
+                Command::SetOutputFormat(format),
+            )
+        }));
+        if self.loaded_data.is_none() || self.loaded_tabs().is_some() {
+            actions.extend(host::TabGrouping::all().iter().map(|&grouping| {
+                (
+                    tr!("palette-group-by", grouping = grouping.label()),
+                    Command::SetTabGrouping(grouping),
+                )
+            }));
+        }
+        actions.push((tr!("select-all-windows"), Command::SelectAllGroups(true)));
+        actions.push((tr!("select-no-windows"), Command::SelectAllGroups(false)));
+        actions.push((tr!("menu-invert-selection"), Command::InvertGroupSelection));
+        if self.undo_history.can_undo() {
+            actions.push((tr!("menu-undo"), Command::Undo));
+        }
+        if self.undo_history.can_redo() {
+            actions.push((tr!("menu-redo"), Command::Redo));
+        }
+        actions.extend(self.presets.iter().enumerate().map(|(index, preset)| {
+            (
+                tr!("palette-apply-preset", name = preset.name),
+                Command::ApplyPreset(index),
+            )
+        }));
+        actions
+    }
+
+    /// Number of windows that will be included in exported links.
+    fn selected_window_count(&self) -> usize {
+        self.selected_group_keys().len()
+    }
+
+    /// The save path with all template placeholders replaced.
+    fn expanded_save_path(&self) -> String {
+        let values = crate::save_path::TemplateValues {
+            profile: crate::save_path::profile_name_from_session_path(self.loaded_path.as_ref())
+                .unwrap_or_default(),
+            windows: self.selected_window_count(),
+            format: self.output_options.format.as_str().to_owned(),
+            ext: host::file_extension(self.output_options.format).to_owned(),
+        };
+        crate::save_path::expand_template(&self.save_path, &values)
+    }
+
+    /// The path that links will be written to, including the file extension
+    /// and the number that is added to avoid overwriting an existing file.
+    fn final_save_path(&mut self) -> PathBuf {
+        let path = crate::save_path::with_extension(
+            PathBuf::from(self.expanded_save_path()),
+            host::file_extension(self.output_options.format),
+            &host::file_extensions(),
+        );
+        let auto_increment = self.output_options.auto_increment;
+        if let Some((cached_path, cached_increment, final_path)) = &self.final_save_path
+            && *cached_path == path
+            && *cached_increment == auto_increment
+        {
+            return final_path.clone();
+        }
+        #[cfg(not(target_family = "wasm"))]
+        let final_path = if auto_increment {
+            crate::save_path::next_free_path(&path).unwrap_or_else(|_| path.clone())
+        } else {
+            path.clone()
+        };
+        #[cfg(target_family = "wasm")]
+        let final_path = path.clone();
+        self.final_save_path = Some((path, auto_increment, final_path.clone()));
+        final_path
+    }
+
+    fn change_selected_tab_group(
+        &mut self,
+        ctx: &egui::Context,
+        index: u32,
+        open: bool,
+        select: bool,
+    ) {
+        let window = host::WindowKey { open, index };
+        let mut selected = self.selected_group_keys();
+        if select {
+            selected.insert(window);
+        } else {
+            selected.remove(&window);
+        }
+        self.set_selected_groups(ctx, selected);
+    }
+
+    /// All groups in the side panel in the order they are shown, closed
+    /// windows are left out while they are hidden.
+    fn group_keys(&self) -> Vec<host::WindowKey> {
+        [
+            (true, &self.tab_groups.open),
+            (false, &self.tab_groups.closed),
+        ]
+        .into_iter()
+        .filter(|(open, _)| *open || self.show_closed_windows)
+        .flat_map(|(open, groups)| {
+            groups.iter().map(move |group| host::WindowKey {
+                open,
+                index: group.index,
+            })
+        })
+        .collect()
+    }
+
+    /// Groups that will be exported. If the open groups haven't been chosen
+    /// explicitly then all of them except recently closed tabs are exported.
+    fn selected_group_keys(&self) -> BTreeSet<host::WindowKey> {
+        let GenerateOptions {
+            open_group_indexes,
+            closed_group_indexes,
+            ..
+        } = &self.selected_tab_groups;
+        [
+            (true, &self.tab_groups.open, open_group_indexes),
+            (false, &self.tab_groups.closed, closed_group_indexes),
+        ]
+        .into_iter()
+        .flat_map(|(open, groups, selected)| {
+            groups
+                .iter()
+                .filter(move |group| match selected {
+                    Some(selected) => selected.contains(&group.index),
+                    None => !group.is_recently_closed(),
+                })
+                .map(move |group| host::WindowKey {
+                    open,
+                    index: group.index,
+                })
+        })
+        .collect()
+    }
+
+    /// Select exactly these groups. Selecting nothing is allowed, then nothing
+    /// is exported until another group is selected.
+    fn set_selected_groups(&mut self, ctx: &egui::Context, selected: BTreeSet<host::WindowKey>) {
+        let indexes = |open: bool| {
+            Some(
+                selected
+                    .iter()
+                    .filter(|window| window.open == open)
+                    .map(|window| window.index)
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let (open, closed) = (indexes(true), indexes(false));
+        let options = &mut self.selected_tab_groups;
+        if options.open_group_indexes == open && options.closed_group_indexes == closed {
+            return;
+        }
+        options.open_group_indexes = open;
+        options.closed_group_indexes = closed;
+        // Select all native tab groups if the window is selected again:
+        options.excluded_native_groups.retain(|group| {
+            selected.contains(&host::WindowKey {
+                open: group.open,
+                index: group.window,
+            })
+        });
+        self.regenerate_preview(ctx);
+    }
+
+    /// Closed windows that are hidden in the side panel shouldn't be exported
+    /// without the user seeing them, so deselect them.
+    fn deselect_hidden_windows(&mut self, ctx: &egui::Context) {
+        let closed = self.selected_tab_groups.closed_group_indexes.as_ref();
+        if self.show_closed_windows || closed.is_some_and(Vec::is_empty) {
+            return;
+        }
+        let open = self
+            .selected_group_keys()
+            .into_iter()
+            .filter(|window| window.open)
+            .collect();
+        self.set_selected_groups(ctx, open);
+    }
+
+    /// Update the selected groups after a group was clicked in the side panel.
+    fn click_tab_group(
+        &mut self,
+        ctx: &egui::Context,
+        window: host::WindowKey,
+        modifiers: egui::Modifiers,
+    ) {
+        let keys = self.group_keys();
+        let position = |window: host::WindowKey| keys.iter().position(|key| *key == window);
+        let mut selected = self.selected_group_keys();
+        if modifiers.shift
+            && let Some(anchor) = self.group_selection_anchor.and_then(position)
+            && let Some(clicked) = position(window)
+        {
+            if !modifiers.command {
+                selected.clear();
+            }
+            selected.extend(&keys[anchor.min(clicked)..=anchor.max(clicked)]);
+        } else {
+            if modifiers.command {
+                if !selected.remove(&window) {
+                    selected.insert(window);
+                }
+            } else {
+                selected = BTreeSet::from([window]);
+            }
+            self.group_selection_anchor = Some(window);
+        }
+        self.set_selected_groups(ctx, selected);
+    }
+
+    /// Move through the window list with the arrow keys and select windows
+    /// with space.
+    fn window_list_keys(&mut self, ctx: &egui::Context) {
+        let (up, down, space, escape, shift) = ctx.input(|i| {
+            (
+                i.key_pressed(egui::Key::ArrowUp),
+                i.key_pressed(egui::Key::ArrowDown),
+                i.key_pressed(egui::Key::Space),
+                i.key_pressed(egui::Key::Escape),
+                i.modifiers.shift,
+            )
+        });
+        if escape {
+            self.group_cursor = None;
+            self.window_list_focused = false;
+        }
+        if up || down {
+            let keys = self.group_keys();
+            let last = keys.len().saturating_sub(1);
+            let current = self
+                .group_cursor
+                .and_then(|cursor| keys.iter().position(|key| *key == cursor));
+            let next = match current {
+                Some(current) if up => current.saturating_sub(1),
+                Some(current) => (current + 1).min(last),
+                None if up => last,
+                None => 0,
+            };
+            self.group_cursor = keys.get(next).copied();
+            self.scroll_to_group_cursor = true;
+        }
+        if space && let Some(window) = self.group_cursor {
+            let modifiers = if shift {
+                egui::Modifiers::SHIFT
+            } else {
+                egui::Modifiers::COMMAND
+            };
+            self.handle_command(ctx, Command::ClickTabGroup { window, modifiers });
+        }
+    }
+
+    /// Select the groups that aren't selected and deselect the others.
+    fn invert_group_selection(&mut self, ctx: &egui::Context) {
+        let selected = self.selected_group_keys();
+        let inverted = self
+            .group_keys()
+            .into_iter()
+            .filter(|window| !selected.contains(window))
+            .collect();
+        self.set_selected_groups(ctx, inverted);
+    }
+
+    /// Buttons that change which groups are selected in the side panel.
+    fn bulk_selection_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr!("select-label"));
+            if ui.button(tr!("select-all")).clicked() {
+                self.handle_command(ui, Command::SelectAllGroups(true));
+            }
+            if ui.button(tr!("select-none")).clicked() {
+                self.handle_command(ui, Command::SelectAllGroups(false));
+            }
+            if ui.button(tr!("select-invert")).clicked() {
+                self.handle_command(ui, Command::InvertGroupSelection);
+            }
+            if ui
+                .add_enabled(
+                    !self.tab_groups.closed.is_empty(),
+                    egui::Button::new(tr!("select-all-closed")),
+                )
+                .on_hover_text(tr!("select-all-closed-hover"))
+                .clicked()
+            {
+                self.handle_command(ui, Command::SelectClosedGroups);
+            }
+        });
+        if self.selected_tab_groups.selects_nothing() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                tr!("no-windows-selected-warning"),
+            );
+        }
+    }
+
+    /// Select or deselect a native tab group. Selecting a group in a window
+    /// that isn't selected selects only that group in the window.
+    fn change_selected_native_group(
+        &mut self,
+        ctx: &egui::Context,
+        group: NativeGroupId,
+        select: bool,
+    ) {
+        let open = group.open;
+        let Some(window) = self
+            .loaded_tabs()
+            .and_then(|tabs| tabs.window(open, group.window))
+        else {
+            return;
+        };
+        let siblings = window.native_group_ids(open, group.window);
+        let Some(index) = self
+            .tab_groups
+            .group_of_window(open, group.window)
+            .map(|tab_group| tab_group.index)
+        else {
+            return;
+        };
+        let window_selected = self.is_group_selected(open, index);
+        let excluded = &mut self.selected_tab_groups.excluded_native_groups;
+
+        if select {
+            if !window_selected {
+                excluded.extend(siblings.into_iter().filter(|other| *other != group));
+                self.change_selected_tab_group(ctx, index, open, true);
+                return;
+            }
+            excluded.remove(&group);
+        } else {
+            excluded.insert(group);
+            if siblings.iter().all(|other| excluded.contains(other)) {
+                // Nothing left in the window => deselect it:
+                self.change_selected_tab_group(ctx, index, open, false);
+                return;
+            }
+        }
+        self.regenerate_preview(ctx);
+    }
+
+    /// Check if a group is selected in the side panel.
+    fn is_group_selected(&self, open: bool, index: u32) -> bool {
+        self.selected_group_keys()
+            .contains(&host::WindowKey { open, index })
+    }
+
+    /// Copy some text in the background, `message` is shown in the status bar
+    /// once it has been copied.
+    fn copy_to_clipboard(
+        &self,
+        ctx: &egui::Context,
+        content: impl Into<ClipboardContent>,
+        message: String,
+    ) {
+        let content = content.into();
+        self.background.spawn(ctx, async move {
+            Some(write_to_clipboard(content, message).await)
+        });
+    }
+
+    /// Copy links in the chosen clipboard format. The preview is reused if it
+    /// already has the right format and content, otherwise the links are
+    /// generated in the background.
+    fn copy_links_to_clipboard(
+        &mut self,
+        ctx: &egui::Context,
+        options: GenerateOptions,
+        clipboard_format: host::ClipboardFormat,
+        message: String,
+    ) {
+        let format = host::clipboard_link_format(clipboard_format, self.output_options.format);
+        if format == self.output_options.format && options == self.selected_tab_groups {
+            self.copy_to_clipboard(ctx, self.preview.clipboard_content(), message);
+            return;
+        }
+
+        let Some(data) = self.loaded_data.clone() else {
+            return;
+        };
+        self.status_log.push(StatusEvent::progress(
+            Operation::Clipboard,
+            tr!("status-generating-clipboard"),
+        ));
+        self.background.spawn(ctx, async move {
+            let source = match data.to_text_links(options, format).await {
+                Ok(source) => source,
+                Err(e) => {
+                    return Some(Command::SetStatus(StatusEvent::error(
+                        Operation::Clipboard,
+                        tr!("status-clipboard-failed", error = e),
+                    )));
+                }
+            };
+            let content =
+                preview::Preview::new(source, host::preview_syntax(format)).clipboard_content();
+            Some(write_to_clipboard(content, message).await)
+        });
+    }
+
+    /// Copy links to some specific tabs. A single tab is copied as just a
+    /// link, otherwise the tabs are grouped like in the side panel.
+    fn copy_tabs_to_clipboard(
+        &mut self,
+        ctx: &egui::Context,
+        tab_ids: &BTreeSet<TabId>,
+        message: String,
+    ) {
+        let Some(tabs) = self.loaded_data.as_ref().and_then(|data| data.tabs.clone()) else {
+            return;
+        };
+        if tab_ids.len() == 1
+            && let Some(tab) = tab_ids.first().and_then(|id| tabs.tab(*id))
+        {
+            let format =
+                host::clipboard_link_format(self.clipboard_format, self.output_options.format);
+            let url = (self.selected_tab_groups.url_cleaning)
+                .clean(&tab.url)
+                .unwrap_or_else(|| tab.url.clone());
+            let content =
+                preview::link_clipboard_content(&tab.title, &url, host::preview_syntax(format));
+            self.copy_to_clipboard(ctx, content, message);
+            return;
+        }
+
+        let mut options = self.selected_tab_groups.clone();
+        let mut open_groups = Vec::new();
+        let mut closed_groups = Vec::new();
+        for (open, groups, indexes) in [
+            (true, &self.tab_groups.open, &mut open_groups),
+            (false, &self.tab_groups.closed, &mut closed_groups),
+        ] {
+            for group in groups {
+                let index = group.index;
+                let group_tabs = group
+                    .tabs(&tabs, open)
+                    .map(|tab| tab.id)
+                    .collect::<Vec<_>>();
+                if group_tabs.iter().any(|id| tab_ids.contains(id)) {
+                    indexes.push(index);
+                    options
+                        .excluded_tabs
+                        .extend(group_tabs.into_iter().filter(|id| !tab_ids.contains(id)));
+                }
+            }
+        }
+        options.open_group_indexes = Some(open_groups);
+        options.closed_group_indexes = Some(closed_groups);
+        self.copy_links_to_clipboard(ctx, options, self.clipboard_format, message);
+    }
+
+    /// Tabs in the tab list that match the current search and filter.
+    fn visible_tabs(&self) -> BTreeSet<TabId> {
+        self.tab_rows
+            .iter()
+            .filter_map(|row| match row {
+                TabRow::Tab(id) => Some(*id),
+                TabRow::Window { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Update the selection in the tab list after a tab was clicked.
+    fn click_tab(&mut self, id: TabId, modifiers: egui::Modifiers) {
+        let position = |id: TabId| self.tab_rows.iter().position(|row| *row == TabRow::Tab(id));
+        if modifiers.shift
+            && let Some(anchor) = self.tab_selection_anchor.and_then(position)
+            && let Some(clicked) = position(id)
+        {
+            if !modifiers.command {
+                self.selected_tabs.clear();
+            }
+            let range = anchor.min(clicked)..=anchor.max(clicked);
+            for row in &self.tab_rows[range] {
+                if let TabRow::Tab(id) = row {
+                    self.selected_tabs.insert(*id);
+                }
+            }
+            return;
+        }
+
+        if modifiers.command {
+            if !self.selected_tabs.remove(&id) {
+                self.selected_tabs.insert(id);
+            }
+        } else {
+            self.selected_tabs.clear();
+            self.selected_tabs.insert(id);
+        }
+        self.tab_selection_anchor = Some(id);
+    }
+
+    /// Ask the user for a session file, it is loaded once it has been chosen.
+    fn browse_input_file(&mut self, ctx: &egui::Context, frame: Option<&eframe::Frame>) {
+        log::debug!("Browsing for input file");
+
+        #[cfg(not(target_family = "wasm"))]
+        let handle_fut =
+            crate::host::prompt_load_file(frame.map(|frame| frame as &dyn host::DialogParent));
+        #[cfg(target_family = "wasm")]
+        let handle_fut = {
+            let _ = frame;
+            crate::host::prompt_load_file(None)
+        };
+
+        self.background.spawn(ctx, async move {
+            let handle = handle_fut.await?;
+            #[cfg(target_family = "wasm")]
+            let name = handle.file_name();
+            #[cfg(not(target_family = "wasm"))]
+            let name = handle.path().to_string_lossy().into_owned();
+            Some(Command::SetInputPath(name, WebSendable(handle)))
+        });
+    }
+
+    /// React to the keyboard shortcuts in [`shortcuts`].
+    fn handle_shortcuts(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
+        let typing = ctx.wants_keyboard_input();
+        let pressed = |shortcut| ctx.input_mut(|i| i.consume_shortcut(&shortcut));
+
+        if pressed(shortcuts::CHEAT_SHEET) {
+            self.show_shortcuts = !self.show_shortcuts;
+        }
+        if pressed(shortcuts::COMMAND_PALETTE) {
+            self.command_palette.open();
+        }
+        if pressed(shortcuts::OPEN_FILE) {
+            self.browse_input_file(ctx, Some(frame));
+        }
+        if pressed(shortcuts::RELOAD) && !self.input_path.is_empty() {
+            self.load_input_data(ctx);
+        }
+        let can_export = self.loaded_data.is_some() && !self.selected_tab_groups.selects_nothing();
+        if pressed(shortcuts::SAVE) && can_export {
+            self.background.sender().send(ctx, Command::SaveLinksToFile);
+        }
+        let copy = pressed(shortcuts::COPY_LINKS)
+            || (!typing && ctx.input_mut(shortcuts::consume_shifted_copy));
+        if copy && can_export {
+            self.handle_command(ctx, Command::CopyLinks);
+        }
+        // Text fields have their own undo:
+        if !typing {
+            // Check for the shifted shortcut first since it also matches the
+            // shortcut without Shift:
+            if pressed(shortcuts::REDO_SHIFTED) || pressed(shortcuts::REDO) {
+                self.handle_command(ctx, Command::Redo);
+            } else if pressed(shortcuts::UNDO) {
+                self.handle_command(ctx, Command::Undo);
+            }
+        }
+        if pressed(shortcuts::FOCUS_SEARCH) && self.loaded_tabs().is_some() {
+            self.preview_mode = PreviewMode::Tabs;
+            self.focus_tab_search = true;
+        }
+        if !typing && !self.command_palette.is_open() && self.window_list_focused {
+            self.window_list_keys(ctx);
+        }
+    }
+
+    fn loaded_tabs(&self) -> Option<&session::SessionTabs> {
+        self.loaded_data.as_ref()?.tabs.as_deref()
+    }
+
+    /// Update the rows of the tab list after the selected windows, the search
+    /// or the container filter changed.
+    fn rebuild_tab_rows(&mut self) {
+        self.tab_rows.clear();
+        let Some(tabs) = self.loaded_data.as_ref().and_then(|data| data.tabs.clone()) else {
+            return;
+        };
+        let query = self.tab_search.trim().to_lowercase();
+        let container = self.tab_container_filter;
+        let filtered = !query.is_empty() || container.is_some();
+        for (open, groups, selected) in [
+            (
+                true,
+                &self.tab_groups.open,
+                &self.selected_tab_groups.open_group_indexes,
+            ),
+            (
+                false,
+                &self.tab_groups.closed,
+                &self.selected_tab_groups.closed_group_indexes,
+            ),
+        ] {
+            for group in groups {
+                let index = group.index;
+                let is_selected = match selected {
+                    Some(selected) => selected.contains(&index),
+                    None => !group.is_recently_closed(),
+                };
+                if !is_selected {
+                    continue;
+                }
+                let mut matching = group
+                    .tabs(&tabs, open)
+                    .filter(|tab| tab.matches_search(&query))
+                    .filter(|tab| container.is_none_or(|id| tab.user_context_id == id))
+                    .filter(|tab| !self.selected_tab_groups.is_in_excluded_native_group(tab))
+                    .collect::<Vec<_>>();
+                matching.sort_by_cached_key(|tab| self.selected_tab_groups.tab_sort.key(tab));
+                let header = self.tab_rows.len();
+                self.tab_rows.push(TabRow::Window { open, index });
+                self.tab_rows
+                    .extend(matching.into_iter().map(|tab| TabRow::Tab(tab.id)));
+                if filtered && self.tab_rows.len() == header + 1 {
+                    // No matching tabs in this window:
+                    self.tab_rows.pop();
+                }
+            }
+        }
+        let visible = self.visible_tabs();
+        self.selected_tabs.retain(|id| visible.contains(id));
+    }
+
+    /// Find the tabs that match the exclude rules.
+    fn update_rule_excluded_tabs(&mut self) {
+        let excluded = &mut self.selected_tab_groups.rule_excluded_tabs;
+        excluded.clear();
+        self.exclude_rules_error = None;
+        if !self.exclude_rules.enabled {
+            return;
+        }
+        let Some(tabs) = self.loaded_data.as_ref().and_then(|data| data.tabs.clone()) else {
+            return;
+        };
+        match self.exclude_rules.compile() {
+            Ok(rules) => {
+                excluded.extend(rules.excluded_tabs(&tabs, self.selected_tab_groups.url_cleaning))
+            }
+            Err(e) => self.exclude_rules_error = Some(e),
+        }
+    }
+
+    fn compute_statistics(&mut self, ctx: &egui::Context) {
+        let Some(data) = self.loaded_data.clone() else {
+            return;
+        };
+        // Only window names are useful for the "tabs per window" statistics:
+        let groups = if self.selected_tab_groups.grouping == host::TabGrouping::Window {
+            let mut groups = self.tab_groups.clone();
+            for (open, groups) in [(true, &mut groups.open), (false, &mut groups.closed)] {
+                for group in groups {
+                    group.name = self.group_name(open, group).to_owned();
+                }
+            }
+            groups
+        } else {
+            Default::default()
+        };
+        self.background.spawn(ctx, async move {
+            Some(match data.statistics(groups).await {
+                Ok(statistics) => Command::SetStatistics(Arc::new(statistics)),
+                Err(e) => Command::SetStatus(StatusEvent::error(
+                    Operation::Statistics,
+                    tr!("status-statistics-failed", error = e),
+                )),
+            })
+        });
+    }
+
+    /// Switch between grouping tabs by window or into virtual groups. The
+    /// selected groups are reset since their indexes refer to other groups.
+    fn set_tab_grouping(&mut self, ctx: &egui::Context, grouping: host::TabGrouping) {
+        self.undo_history.clear();
+        self.selected_tab_groups.grouping = grouping;
+        self.selected_tab_groups.excluded_native_groups.clear();
+        self.group_cursor = None;
+        self.selected_tab_groups.open_group_indexes = None;
+        self.selected_tab_groups.closed_group_indexes = Some(Vec::new());
+        let Some(data) = self.loaded_data.clone() else {
+            return;
+        };
+        self.status_log.push(StatusEvent::progress(
+            Operation::Grouping,
+            tr!("status-grouping"),
+        ));
+        self.background.spawn(ctx, async move {
+            Some(match data.tab_groups(grouping).await {
+                Ok(all_groups) => Command::ParsedTabGroups(all_groups),
+                Err(e) => Command::SetStatus(StatusEvent::error(
+                    Operation::Grouping,
+                    tr!("status-grouping-failed", error = e),
+                )),
+            })
+        });
+    }
+
+    fn regenerate_preview(&mut self, ctx: &egui::Context) {
+        self.rebuild_tab_rows();
+        let Some(data) = self.loaded_data.clone() else {
+            return;
+        };
+        let options = self.selected_tab_groups.clone();
+        if options.selects_nothing() {
+            self.preview = Default::default();
+            self.status_log.push(StatusEvent::info(
+                Operation::Preview,
+                tr!("status-no-windows"),
+            ));
+            return;
+        }
+        let format = self.output_options.format;
+        self.status_log.push(StatusEvent::progress(
+            Operation::Preview,
+            tr!("status-generating-preview"),
+        ));
+        self.background.spawn(ctx, async move {
+            Some(match data.to_text_links(options, format).await {
+                Ok(source) => {
+                    Command::SetPreview(preview::Preview::new(source, host::preview_syntax(format)))
+                }
+                Err(e) => Command::SetStatus(StatusEvent::error(
+                    Operation::Preview,
+                    tr!("status-preview-failed", error = e),
+                )),
+            })
+        });
+    }
+
+    pub fn load_input_data(&mut self, ctx: &egui::Context) {
+        // The file might have changed since it was loaded (Firefox rewrites
+        // its session files), so the tabs and windows in earlier changes
+        // might no longer exist:
+        self.undo_history.clear();
+        self.exclude_rules_edit = None;
+        self.loaded_path.clone_from(&self.input_path);
+        if cfg!(not(target_family = "wasm")) {
+            // Web builds can't open a file again by its name:
+            self.recent_files.retain(|path| *path != self.input_path);
+            self.recent_files.insert(0, self.input_path.clone());
+            self.recent_files.truncate(RECENT_FILES);
+        }
+
+        let mut data = host::FileInfo::new(PathBuf::from(self.input_path.clone()));
+        data.file_handle = self.input_data.clone();
+        self.loaded_data = Some(data.clone());
+        self.statistics = None;
+        self.selected_tabs.clear();
+        self.tab_container_filter = None;
+        self.history_tab = None;
+        self.url_changes = None;
+        self.selected_tab_groups.excluded_tabs.clear();
+        self.selected_tab_groups.rule_excluded_tabs.clear();
+        self.selected_tab_groups.excluded_native_groups.clear();
+        self.selected_tab_groups.open_group_indexes = None;
+        self.selected_tab_groups.closed_group_indexes = Some(Vec::new());
+        self.selected_tab_groups.window_names = self
+            .window_names
+            .get(&self.loaded_path)
+            .cloned()
+            .unwrap_or_default();
+        self.renaming_window = None;
+        self.group_cursor = None;
+        self.pending_preset = self
+            .presets
+            .iter()
+            .find(|preset| preset.apply_on_load)
+            .cloned();
+        self.status_log.push(StatusEvent::progress(
+            Operation::Load,
+            tr!("status-reading"),
+        ));
+        let grouping = self.selected_tab_groups.grouping;
+
+        self.background.spawn(ctx, {
+            let sender = self.background.sender().clone();
+            let ctx = ctx.clone();
+            async move {
+                if let Err(e) = data.load_data().await {
+                    return Some(Command::SetStatus(StatusEvent::error(
+                        Operation::Load,
+                        tr!("status-read-failed", error = e),
+                    )));
+                };
+                sender.send(&ctx, Command::UpdateLoadedData(data.clone()));
+                loop {
+                    match &data.data {
+                        Some(host::FileData::Compressed { .. }) => {
+                            sender.send(
+                                &ctx,
+                                Command::SetStatus(StatusEvent::progress(
+                                    Operation::Load,
+                                    tr!("status-decompressing"),
+                                )),
+                            );
+                            if let Err(e) = data.decompress_data().await {
+                                return Some(Command::SetStatus(StatusEvent::error(
+                                    Operation::Load,
+                                    tr!("status-decompress-failed", error = e),
+                                )));
+                            }
+                        }
+                        Some(host::FileData::Uncompressed { .. }) => {
+                            sender.send(
+                                &ctx,
+                                Command::SetStatus(StatusEvent::progress(
+                                    Operation::Load,
+                                    tr!("status-parsing"),
+                                )),
+                            );
+                            if let Err(e) = data.parse_session_data().await {
+                                return Some(Command::SetStatus(StatusEvent::error(
+                                    Operation::Load,
+                                    tr!("status-parse-failed", error = e),
+                                )));
+                            }
+                        }
+                        Some(host::FileData::Parsed { .. }) => {
+                            return Some(match data.tab_groups(grouping).await {
+                                Ok(all_groups) => {
+                                    sender.send(
+                                        &ctx,
+                                        Command::SetStatus(StatusEvent::info(
+                                            Operation::Load,
+                                            tr!("status-loaded"),
+                                        )),
+                                    );
+                                    Command::ParsedTabGroups(all_groups)
+                                }
+                                Err(e) => Command::SetStatus(StatusEvent::error(
+                                    Operation::Load,
+                                    tr!("status-list-windows-failed", error = e),
+                                )),
+                            });
+                        }
+                        Some(host::FileData::Chromium { .. }) => {
+                            return Some(match data.get_groups_from_session(true).await {
+                                Ok(all_groups) => {
+                                    sender.send(
+                                        &ctx,
+                                        Command::SetStatus(StatusEvent::info(
+                                            Operation::Load,
+                                            tr!("status-loaded"),
+                                        )),
+                                    );
+                                    Command::ParsedTabGroups(all_groups)
+                                }
+                                Err(e) => Command::SetStatus(StatusEvent::error(
+                                    Operation::Load,
+                                    tr!("status-list-chromium-windows-failed", error = e),
+                                )),
+                            });
+                        }
+                        None => unreachable!("we just loaded the data"),
+                    }
+                    sender.send(&ctx, Command::UpdateLoadedData(data.clone()));
+                }
+            }
+        });
+    }
+
+    /// List of the tabs in the selected windows. Only the visible rows are
+    /// laid out so this stays fast even for sessions with thousands of tabs.
+    fn tab_list_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(tabs) = self.loaded_data.as_ref().and_then(|data| data.tabs.clone()) else {
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("search-label"));
+            let search = egui::TextEdit::singleline(&mut self.tab_search)
+                .hint_text(tr!("search-hint"))
+                .desired_width(200.0)
+                .ui(ui);
+            if std::mem::take(&mut self.focus_tab_search) {
+                search.request_focus();
+            }
+            if search.changed() {
+                self.rebuild_tab_rows();
+            }
+            let mut used_containers = tabs
+                .open
+                .iter()
+                .chain(&tabs.closed)
+                .flat_map(|window| &window.tabs)
+                .map(|tab| tab.user_context_id)
+                .collect::<BTreeSet<_>>();
+            if used_containers.len() > 1 || self.tab_container_filter.is_some() {
+                used_containers.extend(self.tab_container_filter);
+                let previous = self.tab_container_filter;
+                egui::ComboBox::from_id_salt("tab_container_filter")
+                    .selected_text(match self.tab_container_filter {
+                        Some(id) => tabs.containers.name(id),
+                        None => tr!("all-containers"),
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.tab_container_filter,
+                            None,
+                            tr!("all-containers"),
+                        );
+                        for id in used_containers {
+                            ui.selectable_value(
+                                &mut self.tab_container_filter,
+                                Some(id),
+                                tabs.containers.name(id),
+                            );
+                        }
+                    });
+                if previous != self.tab_container_filter {
+                    self.rebuild_tab_rows();
+                }
+            }
+            let filtered = !self.tab_search.is_empty() || self.tab_container_filter.is_some();
+            if filtered && ui.button(tr!("copy-matching-tabs")).clicked() {
+                let matching = self.visible_tabs();
+                self.copy_tabs_to_clipboard(
+                    ui,
+                    &matching,
+                    tr!("status-copied-matching-tabs", count = matching.len()),
+                );
+            }
+            if !self.selected_tabs.is_empty() {
+                if ui
+                    .button(tr!(
+                        "copy-selected-tabs-count",
+                        count = self.selected_tabs.len()
+                    ))
+                    .on_hover_text(ui.ctx().format_shortcut(&shortcuts::COPY_TABS))
+                    .clicked()
+                {
+                    self.handle_command(ui, Command::CopySelectedTabs);
+                }
+                if ui.button(tr!("clear-selection")).clicked() {
+                    self.selected_tabs.clear();
+                }
+            } else {
+                ui.weak(tr!("tab-list-hint"));
+            }
+        });
+
+        let copy_requested = !ui.ctx().wants_keyboard_input()
+            && ui.input(|i| {
+                i.events
+                    .iter()
+                    .any(|event| matches!(event, egui::Event::Copy))
+            });
+        if copy_requested && !self.selected_tabs.is_empty() {
+            self.handle_command(ui, Command::CopySelectedTabs);
+        }
+
+        let row_height =
+            ui.text_style_height(&egui::TextStyle::Body) * 2.0 + ui.spacing().item_spacing.y;
+        let mut clicked = None;
+        let mut copy_single = None;
+
+        egui_extras::TableBuilder::new(ui)
+            .id_salt("tab_list")
+            .sense(egui::Sense::click())
+            .cell_layout(egui::Layout::top_down(egui::Align::LEFT))
+            .column(egui_extras::Column::remainder().clip(true))
+            .body(|body| {
+                body.rows(row_height, self.tab_rows.len(), |mut row| {
+                    let tab = match self.tab_rows[row.index()] {
+                        TabRow::Window { open, index } => {
+                            row.set_hovered(false);
+                            let groups = if open {
+                                &self.tab_groups.open
+                            } else {
+                                &self.tab_groups.closed
+                            };
+                            let name = groups
+                                .iter()
+                                .find(|group| group.index == index)
+                                .map_or("", |group| self.group_name(open, group));
+                            row.col(|ui| {
+                                ui.add_space(row_height / 4.0);
+                                ui.strong(name);
+                            });
+                            return;
+                        }
+                        TabRow::Tab(id) => {
+                            let Some(tab) = tabs.tab(id) else { return };
+                            tab
+                        }
+                    };
+                    let excluded_by_rule = self
+                        .selected_tab_groups
+                        .rule_excluded_tabs
+                        .contains(&tab.id);
+                    let excluded = self.selected_tab_groups.excluded_tabs.contains(&tab.id)
+                        || excluded_by_rule;
+                    row.set_selected(self.selected_tabs.contains(&tab.id));
+
+                    row.col(|ui| {
+                        let mut title = egui::RichText::new(&tab.title);
+                        let mut url = egui::RichText::new(&tab.url).small().weak();
+                        if excluded {
+                            title = title.strikethrough().weak();
+                            url = url.strikethrough();
+                        }
+                        egui::Label::new(title).truncate().selectable(false).ui(ui);
+                        ui.horizontal(|ui| {
+                            if tab.user_context_id != 0 {
+                                let name = tabs.containers.name(tab.user_context_id);
+                                let color = tabs
+                                    .containers
+                                    .color(tab.user_context_id)
+                                    .unwrap_or_else(|| ui.visuals().weak_text_color());
+                                egui::Label::new(egui::RichText::new(name).small().color(color))
+                                    .selectable(false)
+                                    .ui(ui);
+                            }
+                            if let Some(closed_at) = tab.closed_at {
+                                egui::Label::new(
+                                    egui::RichText::new(tr!(
+                                        "tab-closed-at",
+                                        time = statistics::format_date_time(closed_at)
+                                    ))
+                                    .small(),
+                                )
+                                .selectable(false)
+                                .ui(ui);
+                            }
+                            egui::Label::new(url).truncate().selectable(false).ui(ui);
+                        });
+                    });
+
+                    let response = row.response();
+                    if response.clicked() {
+                        let modifiers = response.ctx.input(|i| i.modifiers);
+                        if modifiers.command || modifiers.shift {
+                            clicked = Some((tab.id, modifiers));
+                        } else {
+                            response.ctx.open_url(egui::OpenUrl::new_tab(&tab.url));
+                        }
+                    }
+                    response.context_menu(|ui| {
+                        // Copied links use the same URL as exported links:
+                        let cleaned_url = || {
+                            (self.selected_tab_groups.url_cleaning)
+                                .clean(&tab.url)
+                                .unwrap_or_else(|| tab.url.clone())
+                        };
+                        if ui.button(tr!("open-link")).clicked() {
+                            ui.ctx().open_url(egui::OpenUrl::new_tab(&tab.url));
+                        }
+                        ui.separator();
+                        if ui.button(tr!("copy-link")).clicked() {
+                            copy_single = Some(tab.id);
+                        }
+                        if ui.button(tr!("copy-url")).clicked() {
+                            let url = cleaned_url();
+                            self.copy_to_clipboard(ui.ctx(), url, tr!("status-copied-url"));
+                        }
+                        if ui.button(tr!("copy-title")).clicked() {
+                            self.copy_to_clipboard(
+                                ui.ctx(),
+                                tab.title.clone(),
+                                tr!("status-copied-title"),
+                            );
+                        }
+                        if ui.button(tr!("copy-markdown-link")).clicked() {
+                            let link = session::markdown_link(&tab.title, &cleaned_url());
+                            self.copy_to_clipboard(
+                                ui.ctx(),
+                                link,
+                                tr!("status-copied-markdown-link"),
+                            );
+                        }
+                        ui.separator();
+                        if ui
+                            .button(tr!("show-history", count = tab.history.len()))
+                            .clicked()
+                        {
+                            self.history_tab = Some(tab.id);
+                        }
+                        ui.separator();
+                        let label = if excluded {
+                            tr!("include-in-export")
+                        } else {
+                            tr!("exclude-from-export")
+                        };
+                        if excluded_by_rule {
+                            ui.add_enabled(false, egui::Button::new(label))
+                                .on_disabled_hover_text(tr!("tab-matches-rule"));
+                        } else if ui.button(label).clicked() {
+                            self.background.sender().send(
+                                ui.ctx(),
+                                Command::SetTabExcluded {
+                                    tab: tab.id,
+                                    exclude: !excluded,
+                                },
+                            );
+                        }
+                    });
+                });
+            });
+
+        if let Some((id, modifiers)) = clicked {
+            self.click_tab(id, modifiers);
+        }
+        if let Some(id) = copy_single {
+            self.copy_tabs_to_clipboard(ui, &BTreeSet::from([id]), tr!("status-copied-link"));
+        }
+    }
+
+    /// Select the windows and options that a preset was saved with.
+    fn apply_preset(&mut self, ctx: &egui::Context, preset: SelectionPreset) {
+        if preset.grouping != self.selected_tab_groups.grouping && self.loaded_data.is_some() {
+            // The selected indexes refer to the new groups, so wait for them:
+            self.set_tab_grouping(ctx, preset.grouping);
+            self.pending_preset = Some(preset);
+            return;
+        }
+        let found = preset.apply_to(
+            &mut self.selected_tab_groups,
+            self.tab_groups.open.len(),
+            self.tab_groups.closed.len(),
+        );
+        if !found {
+            self.status_log.push(StatusEvent::info(
+                Operation::Grouping,
+                tr!("status-preset-windows-missing", name = preset.name),
+            ));
+        }
+        self.deselect_hidden_windows(ctx);
+        if let Some(format) = preset.format() {
+            self.output_options.format = format;
+        }
+        self.tab_search = preset.tab_search;
+        self.tab_container_filter = preset.tab_container_filter;
+        self.url_changes = None;
+        self.update_rule_excluded_tabs();
+        self.sort_tab_groups();
+        self.regenerate_preview(ctx);
+    }
+
+    /// Order the groups in the side panel the same way as when exporting.
+    /// Sessions without tab info (from Chromium) can't be sorted or renamed
+    /// when exporting so their windows are kept as they are.
+    fn sort_tab_groups(&mut self) {
+        let tabs = self.loaded_data.as_ref().and_then(|data| data.tabs.clone());
+        let names =
+            if self.selected_tab_groups.grouping == host::TabGrouping::Window && tabs.is_some() {
+                self.selected_tab_groups.window_names.clone()
+            } else {
+                BTreeMap::new()
+            };
+        let window_sort = if tabs.is_some() {
+            self.selected_tab_groups.window_sort
+        } else {
+            host::WindowSort::Session
+        };
+        self.tab_groups.sort(tabs.as_deref(), window_sort, &names);
+    }
+
+    /// The name that is shown for a group, custom names are only used when
+    /// grouping tabs by window in sessions that have tab info.
+    fn group_name<'a>(&'a self, open: bool, group: &'a host::TabGroup) -> &'a str {
+        if self.selected_tab_groups.grouping != host::TabGrouping::Window
+            || self.loaded_tabs().is_none()
+        {
+            return &group.name;
+        }
+        let window = host::WindowKey {
+            open,
+            index: group.index,
+        };
+        self.selected_tab_groups
+            .window_names
+            .get(&window)
+            .unwrap_or(&group.name)
+    }
+
+    /// Set or remove the custom name of a window in the loaded session.
+    fn rename_window(
+        &mut self,
+        ctx: &egui::Context,
+        window: host::WindowKey,
+        name: Option<String>,
+    ) {
+        let names = &mut self.selected_tab_groups.window_names;
+        let changed = match name.map(|name| name.trim().to_owned()) {
+            Some(name) if !name.is_empty() => names.insert(window, name.clone()) != Some(name),
+            _ => names.remove(&window).is_some(),
+        };
+        if !changed {
+            return;
+        }
+        if names.is_empty() {
+            self.window_names.remove(&self.loaded_path);
+        } else {
+            self.window_names
+                .insert(self.loaded_path.clone(), names.clone());
+        }
+        self.sort_tab_groups();
+        self.regenerate_preview(ctx);
+        if self.show_statistics {
+            self.compute_statistics(ctx);
+        }
+    }
+
+    /// Go back to an earlier selection, exclusions, window names and export
+    /// options.
+    fn restore_edit_state(&mut self, ctx: &egui::Context, state: EditState) {
+        state.restore(&mut self.selected_tab_groups, &mut self.exclude_rules);
+        self.url_changes = None;
+        self.update_rule_excluded_tabs();
+        self.deselect_hidden_windows(ctx);
+        let names = &self.selected_tab_groups.window_names;
+        if names.is_empty() {
+            self.window_names.remove(&self.loaded_path);
+        } else {
+            self.window_names
+                .insert(self.loaded_path.clone(), names.clone());
+        }
+        self.sort_tab_groups();
+        self.regenerate_preview(ctx);
+        if self.show_statistics {
+            self.compute_statistics(ctx);
+        }
+    }
+
+    /// Select how windows and the tabs inside them are ordered.
+    fn sort_options_ui(&mut self, ui: &mut egui::Ui) {
+        let (window_sort, tab_sort) = (
+            self.selected_tab_groups.window_sort,
+            self.selected_tab_groups.tab_sort,
+        );
+        egui::Grid::new("sort_options")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(tr!("sort-windows"));
+                ui.add_enabled_ui(self.loaded_tabs().is_some(), |ui| {
+                    egui::ComboBox::from_id_salt("window_sort")
+                        .selected_text(window_sort.label())
+                        .show_ui(ui, |ui| {
+                            for &option in host::WindowSort::all() {
+                                ui.selectable_value(
+                                    &mut self.selected_tab_groups.window_sort,
+                                    option,
+                                    option.label(),
+                                );
+                            }
+                        })
+                })
+                .response
+                .on_disabled_hover_text(tr!("sort-windows-disabled"));
+                ui.end_row();
+
+                ui.label(tr!("sort-tabs"));
+                ui.add_enabled_ui(self.loaded_tabs().is_some(), |ui| {
+                    egui::ComboBox::from_id_salt("tab_sort")
+                        .selected_text(tab_sort.label())
+                        .show_ui(ui, |ui| {
+                            for &option in host::TabSort::all() {
+                                ui.selectable_value(
+                                    &mut self.selected_tab_groups.tab_sort,
+                                    option,
+                                    option.label(),
+                                );
+                            }
+                        })
+                })
+                .response
+                .on_hover_text(tr!("sort-tabs-hover"))
+                .on_disabled_hover_text(tr!("sort-tabs-disabled"));
+                ui.end_row();
+            });
+
+        if window_sort != self.selected_tab_groups.window_sort {
+            self.sort_tab_groups();
+        }
+        if (window_sort, tab_sort)
+            != (
+                self.selected_tab_groups.window_sort,
+                self.selected_tab_groups.tab_sort,
+            )
+        {
+            self.record_options_change(|options| {
+                options.window_sort = window_sort;
+                options.tab_sort = tab_sort;
+            });
+            self.regenerate_preview(ui);
+        }
+    }
+
+    /// Options for cleaning URLs before export and a preview of the URLs
+    /// that they change.
+    fn url_cleaning_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(tabs) = self.loaded_data.as_ref().and_then(|data| data.tabs.clone()) else {
+            return;
+        };
+        egui::CollapsingHeader::new(tr!("clean-urls"))
+            .id_salt("clean_urls")
+            .show(ui, |ui| {
+                let previous = self.selected_tab_groups.url_cleaning;
+                let cleaning = &mut self.selected_tab_groups.url_cleaning;
+                ui.checkbox(&mut cleaning.strip_tracking, tr!("strip-tracking"))
+                    .on_hover_text(tr!("strip-tracking-hover"));
+                ui.checkbox(&mut cleaning.unwrap_reader, tr!("unwrap-reader"))
+                    .on_hover_text(tr!("unwrap-reader-hover"));
+                ui.checkbox(&mut cleaning.unwrap_suspended, tr!("unwrap-suspended"))
+                    .on_hover_text(tr!("unwrap-suspended-hover"));
+                let cleaning = *cleaning;
+                if previous != cleaning {
+                    self.record_options_change(|options| options.url_cleaning = previous);
+                    self.url_changes = None;
+                    self.update_rule_excluded_tabs();
+                    self.regenerate_preview(ui);
+                }
+                if !cleaning.is_enabled() {
+                    return;
+                }
+
+                let changes = self
+                    .url_changes
+                    .get_or_insert_with(|| url_cleaning::preview(&tabs, cleaning));
+                if changes.is_empty() {
+                    ui.weak(tr!("no-links-changed"));
+                    return;
+                }
+                egui::CollapsingHeader::new(tr!("preview-url-changes", count = changes.len()))
+                    .id_salt("preview_url_changes")
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .max_height(200.0)
+                            .show(ui, |ui| {
+                                for change in changes.iter() {
+                                    egui::Label::new(&change.title).truncate().ui(ui);
+                                    egui::Label::new(
+                                        egui::RichText::new(&change.original)
+                                            .small()
+                                            .weak()
+                                            .strikethrough(),
+                                    )
+                                    .truncate()
+                                    .ui(ui);
+                                    egui::Label::new(egui::RichText::new(&change.cleaned).small())
+                                        .truncate()
+                                        .ui(ui);
+                                    ui.add_space(4.0);
+                                }
+                            });
+                    });
+            });
+    }
+
+    /// Save the current selection as a preset or apply a saved preset.
+    fn presets_ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr!("presets"))
+            .id_salt("presets")
+            .show(ui, |ui| {
+                let mut apply = None;
+                let mut remove = None;
+                let mut on_load = None;
+                egui::Grid::new("selection_presets")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for (index, preset) in self.presets.iter().enumerate() {
+                            if ui
+                                .button(&preset.name)
+                                .on_hover_text(tr!("preset-apply-hover"))
+                                .clicked()
+                            {
+                                apply = Some(index);
+                            }
+                            if ui
+                                .selectable_label(preset.apply_on_load, tr!("preset-apply-on-load"))
+                                .on_hover_text(tr!("preset-apply-on-load-hover"))
+                                .clicked()
+                            {
+                                on_load = Some(index);
+                            }
+                            if ui.small_button(tr!("delete")).clicked() {
+                                remove = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if self.presets.is_empty() {
+                    ui.weak(tr!("no-presets"));
+                }
+
+                ui.horizontal(|ui| {
+                    egui::TextEdit::singleline(&mut self.new_preset_name)
+                        .hint_text(tr!("preset-name"))
+                        .desired_width(150.0)
+                        .ui(ui);
+                    let name = self.new_preset_name.trim();
+                    let exists = self.presets.iter().any(|preset| preset.name == name);
+                    let label = if exists {
+                        tr!("replace-preset")
+                    } else {
+                        tr!("save-selection")
+                    };
+                    if ui
+                        .add_enabled(!name.is_empty(), egui::Button::new(label))
+                        .clicked()
+                    {
+                        let preset = SelectionPreset {
+                            tab_search: self.tab_search.clone(),
+                            tab_container_filter: self.tab_container_filter,
+                            ..SelectionPreset::new(
+                                name.to_owned(),
+                                &self.selected_tab_groups,
+                                self.output_options.format,
+                            )
+                        };
+                        match self.presets.iter_mut().find(|p| p.name == preset.name) {
+                            Some(existing) => {
+                                *existing = SelectionPreset {
+                                    apply_on_load: existing.apply_on_load,
+                                    ..preset
+                                }
+                            }
+                            None => self.presets.push(preset),
+                        }
+                        self.new_preset_name.clear();
+                    }
+                });
+
+                if let Some(index) = on_load {
+                    let enable = !self.presets[index].apply_on_load;
+                    // Only a single preset can be applied on load:
+                    for (i, preset) in self.presets.iter_mut().enumerate() {
+                        preset.apply_on_load = enable && i == index;
+                    }
+                }
+                if let Some(index) = remove {
+                    self.presets.remove(index);
+                }
+                if let Some(index) = apply {
+                    self.apply_preset(ui, self.presets[index].clone());
+                }
+            });
     }
 
-    fn change_selected_tab_group(
-        &mut self,
-        ctx: &egui::Context,
-        index: u32,
-        open: bool,
-        select: bool,
-    ) {
-        let (mut indexes, mut other) = (
-            &mut self.selected_tab_groups.open_group_indexes,
-            &mut self.selected_tab_groups.closed_group_indexes,
-        );
-        if !open {
-            std::mem::swap(&mut indexes, &mut other);
-        }
-        if select {
-            let indexes = indexes.get_or_insert_with(Vec::new);
-            other.get_or_insert_with(Vec::new);
-            if !indexes.contains(&index) {
-                indexes.push(index);
-                self.regenerate_preview(ctx)
-            }
-        } else if let Some(indexes) = indexes {
-            let len = indexes.len();
-            indexes.retain(|v| *v != index);
-            if indexes.len() != len {
-                // Something was removed => update preview:
-                if self.selected_tab_groups.selected_groups() == 0 {
-                    // Nothing selected => select all open windows:
-                    self.selected_tab_groups.open_group_indexes = None;
-                    self.selected_tab_groups
-                        .closed_group_indexes
-                        .get_or_insert_with(Vec::new);
+    /// Editor for the rules that exclude tabs based on their URL.
+    fn exclude_rules_ui(&mut self, ui: &mut egui::Ui) {
+        // Tabs can only be excluded from sessions with info about each tab:
+        let can_exclude = self.loaded_data.is_none() || self.loaded_tabs().is_some();
+        egui::CollapsingHeader::new(tr!("exclude-rules"))
+            .id_salt("exclude_rules")
+            .show(ui, |ui| {
+                let previous = self.exclude_rules.clone();
+                let rules = &mut self.exclude_rules;
+                let toggled = ui
+                    .add_enabled(
+                        can_exclude,
+                        egui::Checkbox::new(&mut rules.enabled, tr!("exclude-rules-enabled")),
+                    )
+                    .on_disabled_hover_text(tr!("exclude-disabled"))
+                    .changed();
+                let text_edits = ui
+                    .add_enabled_ui(rules.enabled && can_exclude, |ui| {
+                        egui::Grid::new("exclude_rules")
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                ui.label(tr!("exclude-schemes"))
+                                    .on_hover_text(tr!("exclude-schemes-hover"));
+                                let schemes = egui::TextEdit::singleline(&mut rules.schemes)
+                                    .hint_text("about, moz-extension, file")
+                                    .desired_width(f32::INFINITY)
+                                    .ui(ui);
+                                ui.end_row();
+
+                                ui.label(tr!("exclude-patterns"))
+                                    .on_hover_text(tr!("exclude-patterns-hover"));
+                                let patterns = egui::TextEdit::multiline(&mut rules.patterns)
+                                    .hint_text("https://example.com/*\nregex:^https?://.*\\.pdf$")
+                                    .desired_rows(2)
+                                    .desired_width(f32::INFINITY)
+                                    .ui(ui);
+                                ui.end_row();
+
+                                ui.label(tr!("exclude-domains"))
+                                    .on_hover_text(tr!("exclude-domains-hover"));
+                                let domains = egui::TextEdit::multiline(&mut rules.domains)
+                                    .hint_text("example.com")
+                                    .desired_rows(2)
+                                    .desired_width(f32::INFINITY)
+                                    .ui(ui);
+                                ui.end_row();
+                                [schemes, patterns, domains]
+                            })
+                            .inner
+                    })
+                    .inner;
+                if let Some(error) = &self.exclude_rules_error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
                 }
-                self.regenerate_preview(ctx)
-            }
-        }
+                if previous != self.exclude_rules {
+                    self.update_rule_excluded_tabs();
+                    self.regenerate_preview(ui);
+                }
+                // Only the rules change here, so the state from before has the
+                // previous rules:
+                if toggled {
+                    let before = EditState::of(&self.selected_tab_groups, &previous);
+                    self.undo_history.record(before, &self.edit_state());
+                } else if text_edits.iter().any(egui::Response::changed) {
+                    if self.exclude_rules_edit.is_none() {
+                        self.exclude_rules_edit =
+                            Some(EditState::of(&self.selected_tab_groups, &previous));
+                    }
+                } else if !text_edits.iter().any(egui::Response::has_focus)
+                    && let Some(before) = self.exclude_rules_edit.take()
+                {
+                    self.undo_history.record(before, &self.edit_state());
+                }
+            });
     }
 
-    fn regenerate_preview(&mut self, ctx: &egui::Context) {
-        let Some(data) = self.loaded_data.clone() else {
+    /// Window that lists all pages in a tab's back/forward history.
+    fn tab_history_ui(&mut self, ctx: &egui::Context) {
+        let Some(tab) = self
+            .history_tab
+            .and_then(|id| Some(self.loaded_tabs()?.tab(id)?.clone()))
+        else {
+            self.history_tab = None;
             return;
         };
-        let options = self.selected_tab_groups.clone();
-        self.status = "Generating preview".to_string();
-        self.background.spawn(ctx, async move {
-            Some(match data.to_text_links(options).await {
-                Ok(preview) => Command::SetPreview(preview),
-                Err(e) => Command::SetStatus(format!("Failed to generate preview: {e}")),
-            })
-        });
-    }
-
-    pub fn load_input_data(&mut self, ctx: &egui::Context) {
-        self.loaded_path.clone_from(&self.input_path);
-
-        let mut data = host::FileInfo::new(PathBuf::from(self.input_path.clone()));
-        data.file_handle = self.input_data.clone();
-        self.loaded_data = Some(data.clone());
-        self.selected_tab_groups.open_group_indexes = None;
-        self.selected_tab_groups.closed_group_indexes = Some(Vec::new());
-        self.status = "Reading input file".to_string();
 
-        self.background.spawn(ctx, {
-            let sender = self.background.sender().clone();
-            let ctx = ctx.clone();
-            async move {
-                if let Err(e) = data.load_data().await {
-                    return Some(Command::SetStatus(format!("Failed to read file: {e}")));
-                };
-                sender.send(&ctx, Command::UpdateLoadedData(data.clone()));
-                loop {
-                    match &data.data {
-                        Some(host::FileData::Compressed { .. }) => {
-                            sender.send(&ctx, Command::SetStatus("Decompressing data".to_string()));
-                            if let Err(e) = data.decompress_data().await {
-                                return Some(Command::SetStatus(format!(
-                                    "Failed to decompress data: {e}"
-                                )));
-                            }
-                        }
-                        Some(host::FileData::Uncompressed { .. }) => {
-                            sender
-                                .send(&ctx, Command::SetStatus("Parsing session data".to_string()));
-                            if let Err(e) = data.parse_session_data().await {
-                                return Some(Command::SetStatus(format!(
-                                    "Failed to parse session data: {e}"
-                                )));
+        let mut open = true;
+        let mut copy_urls = false;
+        egui::Window::new(tr!("tab-history-title"))
+            .id(egui::Id::new("tab_history"))
+            .open(&mut open)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                egui::Label::new(egui::RichText::new(&tab.title).strong())
+                    .truncate()
+                    .ui(ui);
+                ui.horizontal(|ui| {
+                    ui.label(tr!("history-page-count", count = tab.history.len()));
+                    copy_urls = ui.button(tr!("copy-all-urls")).clicked();
+                });
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("tab_history_entries")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (index, entry) in tab.history.iter().enumerate() {
+                                let number = egui::RichText::new(format!("{}.", index + 1));
+                                if index == tab.history_index {
+                                    ui.label(number.strong())
+                                        .on_hover_text(tr!("current-page-hover"));
+                                } else {
+                                    ui.label(number.weak());
+                                }
+                                egui::Hyperlink::from_label_and_url(&entry.title, &entry.url)
+                                    .open_in_new_tab(true)
+                                    .ui(ui)
+                                    .on_hover_text(&entry.url);
+                                ui.end_row();
                             }
-                        }
-                        Some(host::FileData::Parsed { .. }) => {
-                            return Some(match data.get_groups_from_session(true).await {
-                                Ok(all_groups) => Command::ParsedTabGroups(all_groups),
-                                Err(e) => Command::SetStatus(format!(
-                                    "Failed to list windows in session: {e}"
-                                )),
-                            });
-                        }
-                        Some(host::FileData::Chromium { .. }) => {
-                            return Some(match data.get_groups_from_session(true).await {
-                                Ok(all_groups) => Command::ParsedTabGroups(all_groups),
-                                Err(e) => Command::SetStatus(format!(
-                                    "Failed to list windows in Chromium session: {e}"
-                                )),
-                            });
-                        }
-                        None => unreachable!("we just loaded the data"),
-                    }
-                    sender.send(&ctx, Command::UpdateLoadedData(data.clone()));
-                }
-            }
-        });
+                        });
+                });
+            });
+
+        if copy_urls {
+            let urls = tab
+                .history
+                .iter()
+                .map(|entry| entry.url.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            self.copy_to_clipboard(
+                ctx,
+                urls,
+                tr!("status-copied-urls", count = tab.history.len()),
+            );
+        }
+        if !open {
+            self.history_tab = None;
+        }
     }
 }
 
@@ -311,23 +2412,11 @@ impl eframe::App for FirefoxSessionDataApp {
         while let Some(command) = self.background.poll_work() {
             self.handle_command(ui, command);
         }
+        self.handle_shortcuts(ui, _frame);
+        // Zoom can also be changed with egui's built-in keyboard shortcuts:
+        self.ui_zoom = ui.zoom_factor();
         egui::Panel::top("top_panel").show(ui, |ui| {
-            // The top panel is often a good place for a menu bar:
-
-            egui::MenuBar::new().ui(ui, |ui| {
-                // NOTE: no "File->Quit" menu item on web pages!
-                let is_web = cfg!(target_arch = "wasm32");
-                if !is_web {
-                    ui.menu_button("File", |ui| {
-                        if ui.button("Quit").clicked() {
-                            ui.send_viewport_cmd(egui::ViewportCommand::Close);
-                        }
-                    });
-                    ui.add_space(16.0);
-                }
-
-                egui::widgets::global_theme_preference_buttons(ui);
-            });
+            self.menu_bar_ui(ui, _frame);
         });
 
         if let Some(wizard) = &self.wizard_state {
@@ -337,7 +2426,7 @@ impl eframe::App for FirefoxSessionDataApp {
                 .frame(egui::Frame::popup(ui.style()).inner_margin(30_i8))
                 .show(ui, |ui| {
                     ui.add_space(10.);
-                    ui.strong("Browser Profiles:");
+                    ui.strong(tr!("browser-profiles"));
                     ui.add_space(10.);
                     egui_extras::TableBuilder::new(ui)
                         .sense(egui::Sense::click())
@@ -370,112 +2459,360 @@ impl eframe::App for FirefoxSessionDataApp {
             }
         }
 
+        if self.history_tab.is_some() {
+            self.tab_history_ui(ui);
+        }
+
+        egui::Window::new(tr!("keyboard-shortcuts"))
+            .id(egui::Id::new("keyboard_shortcuts"))
+            .open(&mut self.show_shortcuts)
+            .collapsible(false)
+            .resizable(false)
+            .show(ui, shortcuts::cheat_sheet_ui);
+
+        egui::Window::new(tr!("menu-about"))
+            .id(egui::Id::new("about"))
+            .open(&mut self.show_about)
+            .collapsible(false)
+            .resizable(false)
+            .show(ui, |ui| {
+                ui.heading(env!("CARGO_PKG_NAME"));
+                ui.label(tr!("about-version", version = env!("CARGO_PKG_VERSION")));
+                ui.label(tr!("about-description"));
+                ui.hyperlink(env!("CARGO_PKG_REPOSITORY"));
+                ui.add_space(8.0);
+                powered_by_egui_and_eframe(ui, false);
+            });
+
+        let actions = if self.command_palette.is_open() {
+            self.palette_actions()
+        } else {
+            Vec::new()
+        };
+        match self.command_palette.show(ui, actions) {
+            // The file dialog should be shown on top of the app's window:
+            Some(Command::BrowseInputFile) => self.browse_input_file(ui, Some(_frame)),
+            Some(command) => self.handle_command(ui, command),
+            None => {}
+        }
+
+        if self.show_statistics {
+            egui::Panel::right("statistics")
+                .min_size(200.0)
+                .show(ui, |ui| {
+                    ui.heading(tr!("statistics"));
+                    ui.separator();
+                    egui::ScrollArea::vertical().show(ui, |ui| match &self.statistics {
+                        Some(stats) => statistics::show(ui, stats),
+                        None if self.loaded_tabs().is_some() => {
+                            ui.spinner();
+                        }
+                        None => {
+                            ui.label(tr!("statistics-placeholder"));
+                        }
+                    });
+                });
+        }
+
+        if self.show_status_log {
+            let copy_log = egui::Panel::bottom("status_log")
+                .min_size(80.0)
+                .show(ui, |ui| self.status_log.ui(ui))
+                .inner;
+            if copy_log {
+                self.handle_command(ui, Command::CopyStatusLog);
+            }
+        }
+
         egui::Panel::left("selected_windows")
             .min_size(120.0)
             .show(ui, |ui| {
                 ui.style_mut().interaction.selectable_labels = false;
-                ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
+                let grouping = self.selected_tab_groups.grouping;
+                let session_tabs = self.loaded_data.as_ref().and_then(|data| data.tabs.clone());
+                let can_regroup = self.loaded_data.is_none() || self.loaded_tabs().is_some();
+                ui.horizontal(|ui| {
+                    ui.label(tr!("group-by"));
+                    ui.add_enabled_ui(can_regroup, |ui| {
+                        egui::ComboBox::from_id_salt("tab_grouping")
+                            .selected_text(grouping.label())
+                            .show_ui(ui, |ui| {
+                                for &option in host::TabGrouping::all() {
+                                    if ui
+                                        .selectable_label(option == grouping, option.label())
+                                        .clicked()
+                                        && option != grouping
+                                    {
+                                        self.set_tab_grouping(ui, option);
+                                    }
+                                }
+                            })
+                    })
+                    .response
+                    .on_disabled_hover_text(tr!("regroup-disabled"));
+                });
+                self.sort_options_ui(ui);
+                self.bulk_selection_ui(ui);
+                let selected_groups = self.selected_group_keys();
+                let list = ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
                     egui::warn_if_debug_build(ui);
-                    powered_by_egui_and_eframe(ui);
+                    powered_by_egui_and_eframe(ui, true);
                     ui.label("");
 
-                    egui_extras::TableBuilder::new(ui)
+                    // Tab counts and other details are only known for Firefox sessions:
+                    let show_summary = session_tabs.is_some();
+                    let mut table = egui_extras::TableBuilder::new(ui)
                         .sense(egui::Sense::click())
                         .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
-                        .column(egui_extras::Column::remainder().clip(true))
-                        .body(|mut body| {
-                            let mut open = true;
-                            loop {
-                                if open {
-                                    // Open windows header:
-                                    body.row(20.0, |mut row| {
-                                        row.set_hovered(false);
-                                        row.col(|col| {
-                                            col.strong("Open Windows");
-                                        });
+                        .column(egui_extras::Column::remainder().clip(true));
+                    if show_summary {
+                        table = table
+                            .columns(egui_extras::Column::auto(), 2)
+                            .column(egui_extras::Column::auto().at_most(150.0).clip(true))
+                            .column(egui_extras::Column::auto());
+                    }
+                    table.body(|mut body| {
+                        let mut open = true;
+                        loop {
+                            if open {
+                                // Open windows header:
+                                body.row(20.0, |mut row| {
+                                    row.set_hovered(false);
+                                    row.col(|col| {
+                                        col.strong(grouping.heading(true));
                                     });
-                                } else {
-                                    // Empty space:
-                                    body.row(20.0, |mut row| {
-                                        row.set_hovered(false);
-                                        row.col(|col| {
-                                            col.label("");
-                                        });
+                                    if show_summary {
+                                        summary_header(&mut row);
+                                    }
+                                });
+                            } else {
+                                // Empty space:
+                                body.row(20.0, |mut row| {
+                                    row.set_hovered(false);
+                                    row.col(|col| {
+                                        col.label("");
                                     });
-                                    // Closed windows header:
-                                    body.row(20.0, |mut row| {
-                                        row.set_hovered(false);
-                                        row.col(|col| {
-                                            col.strong("Closed Windows");
+                                });
+                                // Closed windows header:
+                                body.row(20.0, |mut row| {
+                                    row.set_hovered(false);
+                                    row.col(|col| {
+                                        col.strong(grouping.heading(false));
+                                    });
+                                    if show_summary {
+                                        summary_header(&mut row);
+                                    }
+                                });
+                            }
+
+                            for group in if open {
+                                &self.tab_groups.open
+                            } else {
+                                &self.tab_groups.closed
+                            } {
+                                let index = group.index;
+                                let window = host::WindowKey { open, index };
+                                let name = self.group_name(open, group).to_owned();
+                                let can_rename = grouping == host::TabGrouping::Window
+                                    && session_tabs.is_some()
+                                    && !group.is_recently_closed();
+                                let is_selected = selected_groups.contains(&window);
+                                body.row(20.0, |mut row| {
+                                    row.set_selected(is_selected);
+                                    let renaming = self
+                                        .renaming_window
+                                        .as_mut()
+                                        .filter(|(renamed, _)| *renamed == window);
+                                    if let Some((_, new_name)) = renaming {
+                                        let mut finished = false;
+                                        row.col(|ui| {
+                                            let edit = egui::TextEdit::singleline(new_name)
+                                                .hint_text(group.name.as_str())
+                                                .desired_width(f32::INFINITY)
+                                                .ui(ui);
+                                            if edit.lost_focus() {
+                                                if !ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                                                    self.background.sender().send(
+                                                        ui.ctx(),
+                                                        Command::RenameWindow {
+                                                            window,
+                                                            name: Some(std::mem::take(new_name)),
+                                                        },
+                                                    );
+                                                }
+                                                finished = true;
+                                            } else if !edit.has_focus() {
+                                                edit.request_focus();
+                                            }
                                         });
+                                        if finished {
+                                            self.renaming_window = None;
+                                        }
+                                        return;
+                                    }
+                                    row.col(|ui| {
+                                        ui.label(name.as_str());
                                     });
-                                }
+                                    let summary = &group.summary;
+                                    if show_summary {
+                                        row.col(|ui| {
+                                            ui.label(summary.tab_count.to_string());
+                                        });
+                                        row.col(|ui| {
+                                            if summary.pinned_count > 0 {
+                                                ui.label(summary.pinned_count.to_string());
+                                            }
+                                        });
+                                        row.col(|ui| {
+                                            if let Some(title) = &summary.selected_title {
+                                                ui.label(title.as_str());
+                                            }
+                                        });
+                                        row.col(|ui| {
+                                            if let Some(time) = summary.last_active {
+                                                ui.label(statistics::format_date(time));
+                                            }
+                                        });
+                                    }
+                                    if self.group_cursor == Some(window) {
+                                        row.set_hovered(true);
+                                        if std::mem::take(&mut self.scroll_to_group_cursor) {
+                                            row.response().scroll_to_me(None);
+                                        }
+                                    }
+                                    let mut response = row.response();
+                                    if show_summary || can_rename {
+                                        response = response.on_hover_ui(|ui| {
+                                            if show_summary {
+                                                for title in &summary.first_titles {
+                                                    ui.label(title.as_str());
+                                                }
+                                                let more =
+                                                    summary.tab_count - summary.first_titles.len();
+                                                if more > 0 {
+                                                    ui.weak(tr!("more-tabs", count = more));
+                                                }
+                                            }
+                                            if can_rename {
+                                                if show_summary {
+                                                    ui.separator();
+                                                }
+                                                if name != group.name {
+                                                    ui.weak(tr!("renamed-from", name = group.name));
+                                                } else {
+                                                    ui.weak(tr!("double-click-rename"));
+                                                }
+                                            }
+                                        });
+                                    }
+                                    if can_rename && response.double_clicked() {
+                                        self.renaming_window = Some((window, name));
+                                    } else if response.clicked() {
+                                        self.window_list_focused = true;
+                                        self.background.sender().send(
+                                            &row.response().ctx,
+                                            Command::ClickTabGroup {
+                                                window,
+                                                modifiers: row
+                                                    .response()
+                                                    .ctx
+                                                    .input(|i| i.modifiers),
+                                            },
+                                        );
+                                    }
+                                });
 
-                                for (index, group) in if open {
-                                    self.tab_groups.open.iter()
-                                } else {
-                                    self.tab_groups.closed.iter()
-                                }
-                                .enumerate()
-                                {
-                                    let index = u32::try_from(index).unwrap();
+                                let Some((window_index, window)) = session_tabs
+                                    .as_ref()
+                                    .filter(|_| grouping == host::TabGrouping::Window)
+                                    .zip(group.window)
+                                    .and_then(|(tabs, index)| {
+                                        Some((index, tabs.window(open, index)?))
+                                    })
+                                else {
+                                    continue;
+                                };
+                                for id in window.native_group_ids(open, window_index) {
+                                    let native = id.group.as_ref().and_then(|group| {
+                                        window.groups.iter().find(|g| g.id == *group)
+                                    });
                                     body.row(20.0, |mut row| {
-                                        let is_selected = if open {
-                                            self.selected_tab_groups.open_group_indexes.as_ref()
-                                        } else {
-                                            self.selected_tab_groups.closed_group_indexes.as_ref()
-                                        }
-                                        .is_some_and(|indexes| indexes.contains(&index));
+                                        let is_selected = selected_groups
+                                            .contains(&host::WindowKey { open, index })
+                                            && !self
+                                                .selected_tab_groups
+                                                .excluded_native_groups
+                                                .contains(&id);
                                         row.set_selected(is_selected);
                                         row.col(|ui| {
-                                            ui.label(group.name.as_str());
+                                            ui.add_space(12.0);
+                                            match native {
+                                                Some(native) => {
+                                                    let (rect, _) = ui.allocate_exact_size(
+                                                        egui::vec2(8.0, 8.0),
+                                                        egui::Sense::hover(),
+                                                    );
+                                                    ui.painter().circle_filled(
+                                                        rect.center(),
+                                                        4.0,
+                                                        containers::firefox_color(&native.color),
+                                                    );
+                                                    ui.label(native.name.as_str());
+                                                    if native.collapsed {
+                                                        ui.weak(tr!("collapsed"));
+                                                    }
+                                                }
+                                                None => {
+                                                    ui.weak(tr!("ungrouped-tabs"));
+                                                }
+                                            }
                                         });
                                         if row.response().clicked() {
+                                            self.window_list_focused = true;
                                             self.background.sender().send(
                                                 &row.response().ctx,
-                                                Command::ChangeTabGroupSelection {
-                                                    open,
-                                                    index,
+                                                Command::ChangeNativeGroupSelection {
+                                                    group: id.clone(),
                                                     select: !is_selected,
                                                 },
                                             );
                                         }
                                     });
                                 }
+                            }
 
-                                if open {
-                                    open = false;
-                                } else {
-                                    break;
-                                }
+                            if open && self.show_closed_windows {
+                                open = false;
+                            } else {
+                                break;
                             }
-                        });
+                        }
+                    });
+                });
+                let clicked_elsewhere = ui.input(|i| {
+                    i.pointer.any_pressed()
+                        && i.pointer
+                            .interact_pos()
+                            .is_some_and(|pos| !list.response.rect.contains(pos))
                 });
+                if clicked_elsewhere {
+                    self.window_list_focused = false;
+                }
             });
 
         egui::CentralPanel::default().show(ui, |ui| {
             // The central panel the region left after adding TopPanel's and SidePanel's
             ui.horizontal(|ui| {
-                ui.label("Path to sessionstore file:");
+                ui.label(tr!("input-path"));
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if ui.button("Browse").clicked() {
-                        log::debug!("Pressed browse input path button");
-
-                        #[cfg(not(target_family = "wasm"))]
-                        let handle_fut = crate::host::prompt_load_file(Some(_frame));
-                        #[cfg(target_family = "wasm")]
-                        let handle_fut = crate::host::prompt_load_file(None);
-
-                        self.background.spawn(ui, async move {
-                            let handle = handle_fut.await?;
-                            #[cfg(target_family = "wasm")]
-                            let name = handle.file_name();
-                            #[cfg(not(target_family = "wasm"))]
-                            let name = handle.path().to_string_lossy().into_owned();
-                            Some(Command::SetInputPath(name, WebSendable(handle)))
-                        });
+                    if ui
+                        .button(tr!("browse"))
+                        .on_hover_text(ui.format_shortcut(&shortcuts::OPEN_FILE))
+                        .clicked()
+                    {
+                        self.browse_input_file(ui, Some(_frame));
                     }
-                    if cfg!(not(target_family = "wasm")) && ui.button("Wizard").clicked() {
+                    if cfg!(not(target_family = "wasm")) && ui.button(tr!("wizard")).clicked() {
                         log::debug!("Pressed Wizard button");
                         self.wizard_state = Some(host::FirefoxProfileInfo::all_profiles());
                         ui.request_repaint();
@@ -492,9 +2829,13 @@ impl eframe::App for FirefoxSessionDataApp {
                 })
             });
             ui.horizontal(|ui| {
-                ui.label("Current data was loaded from:");
+                ui.label(tr!("loaded-from"));
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if ui.button("Load new data").clicked() {
+                    if ui
+                        .button(tr!("load-new-data"))
+                        .on_hover_text(ui.format_shortcut(&shortcuts::RELOAD))
+                        .clicked()
+                    {
                         self.load_input_data(ui);
                     }
                     egui::TextEdit::singleline(&mut FakeMutable(self.loaded_path.as_str()))
@@ -503,37 +2844,90 @@ impl eframe::App for FirefoxSessionDataApp {
                 })
             });
 
-            ui.label("Tabs as links:");
+            ui.horizontal(|ui| {
+                ui.label(tr!("tabs-as-links"));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.selectable_value(
+                        &mut self.preview_mode,
+                        PreviewMode::Source,
+                        tr!("preview-source"),
+                    );
+                    if self.preview.syntax.can_render() {
+                        ui.selectable_value(
+                            &mut self.preview_mode,
+                            PreviewMode::Formatted,
+                            tr!("preview-formatted"),
+                        );
+                    }
+                    if self.loaded_tabs().is_some() {
+                        ui.selectable_value(&mut self.preview_mode, PreviewMode::Tabs, tr!("tabs"));
+                    }
+                });
+            });
             ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
                 ui.horizontal(|ui| {
-                    ui.label("Status: ");
-                    egui::TextEdit::singleline(&mut FakeMutable(self.status.as_str()))
+                    ui.label(tr!("status-label"));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let excluded = self.selected_tab_groups.rule_excluded_tabs.len();
+                        if excluded > 0 {
+                            ui.label(tr!("excluded-by-rules", count = excluded))
+                                .on_hover_text(tr!("excluded-by-rules-hover"));
+                        }
+                        let errors = self.status_log.error_count();
+                        let log_button = if errors > 0 {
+                            tr!("status-log-errors", count = errors)
+                        } else {
+                            tr!("status-log")
+                        };
+                        ui.toggle_value(&mut self.show_status_log, log_button);
+                        let latest = self.status_log.latest();
+                        let error_color = latest
+                            .is_some_and(|event| event.level == StatusLevel::Error)
+                            .then_some(ui.visuals().error_fg_color);
+                        egui::TextEdit::singleline(&mut FakeMutable(
+                            latest.map_or("", |event| event.message.as_str()),
+                        ))
                         .desired_width(f32::INFINITY)
+                        .text_color_opt(error_color)
                         .ui(ui);
+                    });
                 });
 
                 ui.horizontal(|ui| {
-                    if ui.button("Copy links to clipboard").clicked() {
-                        let text_to_copy = self.preview.clone();
-                        self.background.spawn(ui, async move {
-                            if let Err(e) =
-                                crate::clipboard::write_text_to_clipboard(text_to_copy.as_str())
-                                    .await
-                            {
-                                Some(Command::SetStatus(format!(
-                                    "Failed to write to clipboard: {e}"
-                                )))
-                            } else {
-                                Some(Command::SetStatus("Copied links to clipboard".to_owned()))
-                            }
-                        });
+                    let selects_nothing = self.selected_tab_groups.selects_nothing();
+                    if ui
+                        .add_enabled(!selects_nothing, egui::Button::new(tr!("copy-links")))
+                        .on_hover_text(ui.format_shortcut(&shortcuts::COPY_LINKS))
+                        .on_disabled_hover_text(tr!("select-at-least-one"))
+                        .clicked()
+                    {
+                        self.handle_command(ui, Command::CopyLinks);
                     }
+                    egui::ComboBox::from_id_salt("clipboard_format")
+                        .selected_text(self.clipboard_format.label())
+                        .show_ui(ui, |ui| {
+                            for &value in host::ClipboardFormat::all() {
+                                ui.selectable_value(
+                                    &mut self.clipboard_format,
+                                    value,
+                                    value.label(),
+                                );
+                            }
+                        })
+                        .response
+                        .on_hover_text(tr!("clipboard-format-hover"));
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if ui.button("Save links to file").clicked() {
+                        if ui
+                            .add_enabled(!selects_nothing, egui::Button::new(tr!("save-links")))
+                            .on_hover_text(ui.format_shortcut(&shortcuts::SAVE))
+                            .on_disabled_hover_text(tr!("select-at-least-one"))
+                            .clicked()
+                        {
                             self.background.sender().send(ui, Command::SaveLinksToFile);
                         }
 
-                        egui::ComboBox::from_label("Output format: ")
+                        let previous_format = self.output_options.format;
+                        egui::ComboBox::new("output_format", tr!("output-format"))
                             .selected_text(self.output_options.format.as_str())
                             .show_ui(ui, |ui| {
                                 for &value in host::FormatInfo::all() {
@@ -547,27 +2941,67 @@ impl eframe::App for FirefoxSessionDataApp {
                             })
                             .response
                             .on_hover_text(self.output_options.format.to_string());
+                        if previous_format != self.output_options.format {
+                            self.regenerate_preview(ui);
+                        }
                     });
                 });
 
+                if self.loaded_tabs().is_some() {
+                    let checkbox = ui
+                        .checkbox(
+                            &mut self.selected_tab_groups.container_labels,
+                            tr!("show-containers"),
+                        )
+                        .on_hover_text(tr!("show-containers-hover"));
+                    if checkbox.changed() {
+                        self.record_options_change(|options| {
+                            options.container_labels = !options.container_labels;
+                        });
+                        self.regenerate_preview(ui);
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("tab-history-label"));
+                        let previous = self.selected_tab_groups.history;
+                        egui::ComboBox::from_id_salt("tab_history")
+                            .selected_text(previous.label())
+                            .show_ui(ui, |ui| {
+                                for &option in host::TabHistory::all() {
+                                    ui.selectable_value(
+                                        &mut self.selected_tab_groups.history,
+                                        option,
+                                        option.label(),
+                                    );
+                                }
+                            })
+                            .response
+                            .on_hover_text(tr!("tab-history-hover"));
+                        if previous != self.selected_tab_groups.history {
+                            self.record_options_change(|options| options.history = previous);
+                            self.regenerate_preview(ui);
+                        }
+                    });
+                    self.url_cleaning_ui(ui);
+                }
+                self.exclude_rules_ui(ui);
+                self.presets_ui(ui);
+
                 if cfg!(not(target_family = "wasm")) {
                     ui.label("");
 
                     ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.output_options.create_folder, tr!("create-folder"));
+                        ui.checkbox(&mut self.output_options.overwrite, tr!("overwrite-file"));
                         ui.checkbox(
-                            &mut self.output_options.create_folder,
-                            "Create folder if it doesn't exist",
-                        );
-                        ui.checkbox(
-                            &mut self.output_options.overwrite,
-                            "Overwrite file if it already exists",
+                            &mut self.output_options.auto_increment,
+                            tr!("auto-increment"),
                         );
                     });
 
                     ui.horizontal(|ui| {
-                        ui.label("File path to write links to:");
+                        ui.label(tr!("save-path"));
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.button("Browse").clicked() {
+                            if ui.button(tr!("browse")).clicked() {
                                 #[cfg(not(target_family = "wasm"))]
                                 let handle_fut = crate::host::prompt_save_file(Some(_frame));
                                 #[cfg(target_family = "wasm")]
@@ -584,41 +3018,116 @@ impl eframe::App for FirefoxSessionDataApp {
                             }
                             egui::TextEdit::singleline(&mut self.save_path)
                                 .desired_width(f32::INFINITY)
-                                .ui(ui);
+                                .ui(ui)
+                                .on_hover_ui(|ui| {
+                                    ui.label(tr!("save-path-placeholders"));
+                                    for (placeholder, description) in crate::save_path::PLACEHOLDERS
+                                    {
+                                        let description = i18n::message(description, &[]);
+                                        ui.label(format!("{placeholder} - {description}"));
+                                    }
+                                });
                         })
                     });
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("save-path-expanded"));
+                        let final_path = self.final_save_path();
+                        egui::TextEdit::singleline(&mut FakeMutable(&final_path.to_string_lossy()))
+                            .desired_width(f32::INFINITY)
+                            .ui(ui);
+                    });
                 }
 
                 ui.label("");
 
                 // Item with flexible height has to be rendered last when we
                 // already know how much space we have used for other items:
-                egui::ScrollArea::vertical().show(ui, |ui| {
-                    ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
-                        egui::TextEdit::multiline(&mut FakeMutable(self.preview.as_str()))
-                            .desired_rows(100)
-                            .desired_width(f32::INFINITY)
-                            .ui(ui);
-                    });
-                });
+                match self.preview_mode {
+                    PreviewMode::Tabs if self.loaded_tabs().is_some() => {
+                        ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
+                            self.tab_list_ui(ui);
+                        });
+                    }
+                    PreviewMode::Formatted if self.preview.syntax.can_render() => {
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
+                                preview::show_blocks(ui, &self.preview.blocks);
+                            });
+                        });
+                    }
+                    _ => {
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
+                                egui::TextEdit::multiline(&mut FakeMutable(
+                                    self.preview.source.as_str(),
+                                ))
+                                .desired_rows(100)
+                                .desired_width(f32::INFINITY)
+                                .ui(ui);
+                            });
+                        });
+                    }
+                }
             });
         });
     }
 }
 
-fn powered_by_egui_and_eframe(ui: &mut egui::Ui) {
-    ui.horizontal(|ui| {
-        ui.spacing_mut().item_spacing.x = 0.0;
-        ui.label(" and ");
-        ui.hyperlink_to(
-            "eframe",
-            "https://github.com/emilk/egui/tree/master/crates/eframe",
-        );
-        ui.label(".");
-    });
-    ui.horizontal(|ui| {
-        ui.spacing_mut().item_spacing.x = 0.0;
-        ui.label("Powered by ");
-        ui.hyperlink_to("egui", "https://github.com/emilk/egui");
-    });
+/// Write to the clipboard and return a status message with the result.
+async fn write_to_clipboard(content: ClipboardContent, message: String) -> Command {
+    Command::SetStatus(
+        if let Err(e) = crate::clipboard::write_to_clipboard(&content).await {
+            StatusEvent::error(
+                Operation::Clipboard,
+                tr!("status-clipboard-write-failed", error = e),
+            )
+        } else {
+            StatusEvent::info(Operation::Clipboard, message)
+        },
+    )
+}
+
+/// Credits for the GUI libraries. Set `bottom_up` when the lines are added
+/// from the bottom of the layout, then the last line has to be added first.
+fn powered_by_egui_and_eframe(ui: &mut egui::Ui, bottom_up: bool) {
+    let first_line = |ui: &mut egui::Ui| {
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 0.0;
+            ui.label(format!("{} ", tr!("powered-by")));
+            ui.hyperlink_to("egui", "https://github.com/emilk/egui");
+        });
+    };
+    let second_line = |ui: &mut egui::Ui| {
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 0.0;
+            ui.label(format!(" {} ", tr!("powered-by-and")));
+            ui.hyperlink_to(
+                "eframe",
+                "https://github.com/emilk/egui/tree/master/crates/eframe",
+            );
+            ui.label(tr!("powered-by-end"));
+        });
+    };
+    if bottom_up {
+        second_line(ui);
+        first_line(ui);
+    } else {
+        first_line(ui);
+        second_line(ui);
+    }
+}
+
+/// Column headings for the tab counts and other details of each window in the
+/// side panel.
+fn summary_header(row: &mut egui_extras::TableRow<'_, '_>) {
+    for heading in [
+        tr!("tabs"),
+        tr!("summary-pinned"),
+        tr!("summary-active-tab"),
+        tr!("summary-last-active"),
+    ] {
+        row.col(|ui| {
+            ui.strong(heading);
+        });
+    }
 }
@@ -1,6 +1,19 @@
 #[cfg(target_family = "wasm")]
 use wasm_bindgen::prelude::*;
 
+/// Data to write to the clipboard. Applications that understand HTML will
+/// paste the rich version while other applications use the plain text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClipboardContent {
+    pub text: String,
+    pub html: Option<String>,
+}
+impl From<String> for ClipboardContent {
+    fn from(text: String) -> Self {
+        Self { text, html: None }
+    }
+}
+
 #[cfg(target_family = "wasm")]
 #[wasm_bindgen::prelude::wasm_bindgen]
 extern "C" {
@@ -15,10 +28,49 @@ pub async fn write_text_to_clipboard(text: &str) -> Result<(), String> {
         .map_err(|e| e.as_string().unwrap_or_default())
 }
 
+#[cfg(target_family = "wasm")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+extern "C" {
+    /// <https://developer.mozilla.org/en-US/docs/Web/API/ClipboardItem>
+    type ClipboardItem;
+
+    #[wasm_bindgen(catch, constructor, js_class = "ClipboardItem")]
+    fn new_clipboard_item(items: &js_sys::Object) -> Result<ClipboardItem, JsValue>;
+
+    /// <https://developer.mozilla.org/en-US/docs/Web/API/Clipboard/write>
+    #[wasm_bindgen(catch, js_name = "write", js_namespace = ["navigator", "clipboard"])]
+    async fn write_items_to_web_clipboard(items: &js_sys::Array) -> Result<(), JsValue>;
+}
+#[cfg(target_family = "wasm")]
+pub async fn write_to_clipboard(content: &ClipboardContent) -> Result<(), String> {
+    let Some(html) = &content.html else {
+        return write_text_to_clipboard(&content.text).await;
+    };
+    let to_error = |e: JsValue| e.as_string().unwrap_or_default();
+
+    let items = js_sys::Object::new();
+    for (mime, data) in [("text/plain", &content.text), ("text/html", html)] {
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type(mime);
+        let blob = web_sys::Blob::new_with_str_sequence_and_options(
+            &js_sys::Array::of1(&JsValue::from_str(data)),
+            &options,
+        )
+        .map_err(to_error)?;
+        js_sys::Reflect::set(&items, &JsValue::from_str(mime), &blob).map_err(to_error)?;
+    }
+    let item = new_clipboard_item(&items).map_err(to_error)?;
+    write_items_to_web_clipboard(&js_sys::Array::of1(&item))
+        .await
+        .map_err(to_error)
+}
+
 #[cfg(not(target_family = "wasm"))]
 static CLIPBOARD: std::sync::Mutex<Option<arboard::Clipboard>> = std::sync::Mutex::new(None);
 #[cfg(not(target_family = "wasm"))]
-pub async fn write_text_to_clipboard(text: &str) -> Result<(), String> {
+fn with_clipboard(
+    f: impl FnOnce(&mut arboard::Clipboard) -> Result<(), arboard::Error>,
+) -> Result<(), String> {
     let mut guard = CLIPBOARD.lock().unwrap();
     let clipboard = if let Some(clipboard) = &mut *guard {
         clipboard
@@ -26,8 +78,18 @@ pub async fn write_text_to_clipboard(text: &str) -> Result<(), String> {
         let clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
         guard.insert(clipboard)
     };
-    clipboard.set_text(text).map_err(|e| e.to_string())?;
-    Ok(())
+    f(clipboard).map_err(|e| e.to_string())
+}
+#[cfg(not(target_family = "wasm"))]
+pub async fn write_text_to_clipboard(text: &str) -> Result<(), String> {
+    with_clipboard(|clipboard| clipboard.set_text(text))
+}
+#[cfg(not(target_family = "wasm"))]
+pub async fn write_to_clipboard(content: &ClipboardContent) -> Result<(), String> {
+    with_clipboard(|clipboard| match &content.html {
+        Some(html) => clipboard.set_html(html, Some(&content.text)),
+        None => clipboard.set_text(&content.text),
+    })
 }
 
 pub fn cleanup_clipboard() {
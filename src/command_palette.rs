@@ -0,0 +1,152 @@
+//! A searchable list of actions that is opened with a keyboard shortcut, so
+//! that every action can be found without going through menus.
+
+use std::cmp::Reverse;
+
+use egui::Widget;
+
+use crate::i18n::tr;
+
+/// Extra score when a character directly follows the previous match.
+const CONSECUTIVE_BONUS: i32 = 5;
+/// Extra score when a character is at the start of a word.
+const WORD_START_BONUS: i32 = 3;
+
+#[derive(Debug, Default)]
+pub struct CommandPalette {
+    open: bool,
+    query: String,
+    /// Index of the highlighted action among the matching actions.
+    selected: usize,
+}
+
+impl CommandPalette {
+    pub fn open(&mut self) {
+        self.open = true;
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Show the palette if it is open. Returns the action that was chosen,
+    /// the palette is closed afterwards.
+    pub fn show<T>(&mut self, ctx: &egui::Context, actions: Vec<(String, T)>) -> Option<T> {
+        if !self.open {
+            return None;
+        }
+        let mut matches = actions
+            .into_iter()
+            .filter_map(|(label, action)| Some((fuzzy_score(&self.query, &label)?, label, action)))
+            .collect::<Vec<_>>();
+        // Stable sort, so actions that match equally well keep their order:
+        matches.sort_by_key(|(score, ..)| Reverse(*score));
+
+        let (up, down, enter) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            )
+        });
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if down {
+            self.selected += 1;
+        }
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+        let mut chosen = (enter && !matches.is_empty()).then_some(self.selected);
+
+        let modal_response = egui::Modal::new(egui::Id::new("command_palette")).show(ctx, |ui| {
+            ui.set_width(400.0);
+            let edit = egui::TextEdit::singleline(&mut self.query)
+                .hint_text(tr!("palette-hint"))
+                .desired_width(f32::INFINITY)
+                .ui(ui);
+            edit.request_focus();
+            if edit.changed() {
+                self.selected = 0;
+            }
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    if matches.is_empty() {
+                        ui.weak(tr!("palette-no-matches"));
+                    }
+                    for (index, (_, label, _)) in matches.iter().enumerate() {
+                        let response = ui.selectable_label(index == self.selected, label.as_str());
+                        if index == self.selected && (up || down) {
+                            response.scroll_to_me(None);
+                        }
+                        if response.clicked() {
+                            chosen = Some(index);
+                        }
+                    }
+                });
+        });
+        if modal_response.should_close() || chosen.is_some() {
+            self.open = false;
+        }
+        chosen
+            .and_then(|index| matches.into_iter().nth(index))
+            .map(|(_, _, action)| action)
+    }
+}
+
+/// Score how well `query` matches `text`, `None` if the characters of the
+/// query don't all appear in the text in the same order.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous = None;
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|t| *t == c)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += CONSECUTIVE_BONUS;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += WORD_START_BONUS;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn characters_must_appear_in_order() {
+        assert!(fuzzy_score("cpl", "Copy links").is_some());
+        assert!(fuzzy_score("lc", "Copy links").is_none());
+        assert!(fuzzy_score("x", "Copy links").is_none());
+    }
+
+    #[test]
+    fn ignores_case_and_whitespace() {
+        assert_eq!(
+            fuzzy_score("COPY LINKS", "Copy links"),
+            fuzzy_score("copylinks", "Copy links")
+        );
+        assert_eq!(fuzzy_score("", "Copy links"), Some(0));
+    }
+
+    #[test]
+    fn consecutive_and_word_start_matches_score_higher() {
+        // "c" at the start of a word, then "o" and "p" directly after it:
+        assert_eq!(
+            fuzzy_score("cop", "Copy"),
+            Some(3 + WORD_START_BONUS + 2 * CONSECUTIVE_BONUS)
+        );
+        assert!(fuzzy_score("save", "Save links") > fuzzy_score("save", "Select all windows"));
+        assert!(fuzzy_score("op", "Open file") > fuzzy_score("op", "Copy"));
+    }
+}
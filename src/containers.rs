@@ -0,0 +1,138 @@
+//! Names and colours of Firefox containers (contextual identities).
+//!
+//! Tabs in the session only store the id of their container, the rest of the
+//! info is stored in the `containers.json` file inside the Firefox profile.
+
+#![cfg_attr(
+    not(feature = "real_data"),
+    allow(dead_code, unused_variables, unused_imports)
+)]
+
+use std::collections::BTreeMap;
+
+use crate::i18n::tr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Container {
+    pub name: String,
+    /// One of Firefox's container colour names, for example `"blue"`.
+    pub color: String,
+}
+
+/// All containers in a Firefox profile, keyed by their `userContextId`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Containers(BTreeMap<u32, Container>);
+
+impl Containers {
+    pub fn insert(&mut self, id: u32, container: Container) {
+        self.0.insert(id, container);
+    }
+    pub fn get(&self, id: u32) -> Option<&Container> {
+        self.0.get(&id)
+    }
+    /// A name for the container with the specified id, even if we don't know
+    /// anything about it.
+    pub fn name(&self, id: u32) -> String {
+        if id == 0 {
+            return tr!("no-container");
+        }
+        self.get(id).map_or_else(
+            || tr!("container-id", id = id),
+            |container| container.name.clone(),
+        )
+    }
+    /// The colour that Firefox uses for the container.
+    pub fn color(&self, id: u32) -> Option<egui::Color32> {
+        Some(firefox_color(&self.get(id)?.color))
+    }
+}
+
+/// Convert one of the colour names that Firefox uses for containers and tab
+/// groups to an actual colour.
+pub fn firefox_color(name: &str) -> egui::Color32 {
+    use egui::Color32;
+
+    // Same colours as Firefox uses in its tab strip:
+    match name {
+        "blue" => Color32::from_rgb(0x37, 0xad, 0xff),
+        "turquoise" | "cyan" => Color32::from_rgb(0x00, 0xc7, 0x9a),
+        "green" => Color32::from_rgb(0x51, 0xcd, 0x00),
+        "yellow" => Color32::from_rgb(0xff, 0xcb, 0x00),
+        "orange" => Color32::from_rgb(0xff, 0x9f, 0x00),
+        "red" => Color32::from_rgb(0xff, 0x61, 0x3d),
+        "pink" => Color32::from_rgb(0xff, 0x4b, 0xda),
+        "purple" => Color32::from_rgb(0xaf, 0x51, 0xf5),
+        _ => Color32::from_rgb(0x7c, 0x7c, 0x7d),
+    }
+}
+
+mod raw {
+    #[derive(Debug, Default, serde::Deserialize)]
+    #[serde(default)]
+    pub struct ContainersFile {
+        pub identities: Vec<Identity>,
+    }
+
+    #[derive(Debug, Default, serde::Deserialize)]
+    #[serde(default, rename_all = "camelCase")]
+    pub struct Identity {
+        pub user_context_id: u32,
+        pub public: bool,
+        /// Only set for containers created by the user.
+        pub name: Option<String>,
+        /// Set instead of `name` for Firefox's default containers.
+        #[serde(rename = "l10nID")]
+        pub l10n_id: Option<String>,
+        pub color: String,
+    }
+}
+
+/// English names of the containers that Firefox creates by default.
+fn default_container_name(l10n_id: &str) -> Option<&'static str> {
+    Some(match l10n_id {
+        "userContextPersonal.label" => "Personal",
+        "userContextWork.label" => "Work",
+        "userContextBanking.label" => "Banking",
+        "userContextShopping.label" => "Shopping",
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "real_data")]
+impl Containers {
+    /// Parse the contents of a `containers.json` file.
+    pub fn from_json(data: &[u8]) -> Result<Self, String> {
+        let file = serde_json::from_slice::<raw::ContainersFile>(data)
+            .map_err(|e| format!("failed to parse containers.json: {e}"))?;
+        Ok(Self(
+            file.identities
+                .into_iter()
+                .filter(|identity| identity.public)
+                .filter_map(|identity| {
+                    let name = identity.name.or_else(|| {
+                        default_container_name(identity.l10n_id.as_deref()?).map(str::to_owned)
+                    })?;
+                    Some((
+                        identity.user_context_id,
+                        Container {
+                            name,
+                            color: identity.color,
+                        },
+                    ))
+                })
+                .collect(),
+        ))
+    }
+
+    /// Read `containers.json` from the profile that a session file was loaded
+    /// from.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn load_for_session(session_path: &std::path::Path) -> Result<Self, String> {
+        let profile = crate::save_path::profile_dir_from_session_path(session_path)
+            .ok_or("could not find the profile folder")?;
+        let path = profile.join("containers.json");
+        let data =
+            std::fs::read(&path).map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        Self::from_json(&data)
+    }
+}
@@ -0,0 +1,208 @@
+//! User defined rules that leave tabs with unwanted URLs out of exports, for
+//! example `about:newtab` or extension pages.
+
+use crate::{
+    i18n::tr,
+    session::{SessionTabs, TabId, host},
+    url_cleaning::UrlCleaning,
+};
+
+/// Patterns that start with this prefix are regular expressions instead of
+/// glob patterns.
+const REGEX_PREFIX: &str = "regex:";
+
+/// Rules as the user wrote them, these are persisted between sessions.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ExcludeRules {
+    pub enabled: bool,
+    /// URL schemes such as `about` or `file`, separated by spaces or commas.
+    pub schemes: String,
+    /// One pattern per line that is matched against the whole (cleaned) URL. Glob
+    /// patterns support `*` and `?`, lines starting with `regex:` are
+    /// regular expressions.
+    pub patterns: String,
+    /// One domain per line, subdomains are also excluded.
+    pub domains: String,
+}
+impl Default for ExcludeRules {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schemes: "about, moz-extension, file".to_owned(),
+            patterns: String::new(),
+            domains: String::new(),
+        }
+    }
+}
+
+/// Exclude rules that are ready to be matched against URLs.
+#[derive(Debug, Clone, Default)]
+pub struct CompiledRules {
+    schemes: Vec<String>,
+    patterns: Vec<regex::Regex>,
+    domains: Vec<String>,
+}
+
+impl ExcludeRules {
+    /// Parse the rules, fails if a pattern isn't valid.
+    pub fn compile(&self) -> Result<CompiledRules, String> {
+        let lines = |text: &str| {
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        };
+        let patterns = lines(&self.patterns)
+            .into_iter()
+            .map(|pattern| {
+                let regex = match pattern.strip_prefix(REGEX_PREFIX) {
+                    Some(regex) => regex.trim().to_owned(),
+                    None => glob_to_regex(&pattern),
+                };
+                regex::RegexBuilder::new(&regex)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| tr!("error-invalid-pattern", pattern = pattern, error = e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CompiledRules {
+            schemes: self
+                .schemes
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .map(|scheme| scheme.trim_end_matches(':').to_lowercase())
+                .filter(|scheme| !scheme.is_empty())
+                .collect(),
+            patterns,
+            domains: lines(&self.domains)
+                .into_iter()
+                .map(|domain| {
+                    let domain = domain.to_lowercase();
+                    domain
+                        .strip_prefix("www.")
+                        .map_or_else(|| domain.clone(), str::to_owned)
+                })
+                .collect(),
+        })
+    }
+}
+
+impl CompiledRules {
+    /// Check if a tab with this URL should be excluded.
+    pub fn matches(&self, url: &str) -> bool {
+        if let Some((scheme, _)) = url.split_once(':')
+            && self
+                .schemes
+                .iter()
+                .any(|excluded| excluded.eq_ignore_ascii_case(scheme))
+        {
+            return true;
+        }
+        if !self.domains.is_empty()
+            && let Some(domain) = host(url).map(|host| host.to_lowercase())
+            && self.domains.iter().any(|excluded| {
+                domain == *excluded
+                    || domain
+                        .strip_suffix(excluded.as_str())
+                        .is_some_and(|sub| sub.ends_with('.'))
+            })
+        {
+            return true;
+        }
+        self.patterns.iter().any(|pattern| pattern.is_match(url))
+    }
+
+    /// All tabs in a session whose URL matches the rules. The URLs are
+    /// cleaned first, so that the rules see the same URLs as the export.
+    pub fn excluded_tabs(
+        &self,
+        tabs: &SessionTabs,
+        cleaning: UrlCleaning,
+    ) -> impl Iterator<Item = TabId> {
+        tabs.open
+            .iter()
+            .chain(&tabs.closed)
+            .flat_map(|window| window.tabs.iter().chain(&window.closed_tabs))
+            .filter(move |tab| match cleaning.clean(&tab.url) {
+                Some(cleaned) => self.matches(&cleaned),
+                None => self.matches(&tab.url),
+            })
+            .map(|tab| tab.id)
+    }
+}
+
+/// Convert a glob pattern to an anchored regular expression.
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(schemes: &str, patterns: &str, domains: &str) -> CompiledRules {
+        ExcludeRules {
+            enabled: true,
+            schemes: schemes.to_owned(),
+            patterns: patterns.to_owned(),
+            domains: domains.to_owned(),
+        }
+        .compile()
+        .unwrap()
+    }
+
+    #[test]
+    fn glob_is_anchored_and_escaped() {
+        assert_eq!(glob_to_regex("*.example.com/?"), r"^.*\.example\.com/.$");
+        let rules = rules("", "https://*.example.com/*\nhttp://a.com/pag?", "");
+        assert!(rules.matches("https://mail.example.com/inbox"));
+        assert!(!rules.matches("https://example.com/"));
+        assert!(rules.matches("HTTP://A.COM/PAGE"));
+        assert!(!rules.matches("http://a.com/pages"));
+    }
+
+    #[test]
+    fn regex_patterns() {
+        let rules = rules("", "regex: ^https://a\\.com/\\d+$", "");
+        assert!(rules.matches("https://a.com/123"));
+        assert!(!rules.matches("https://a.com/abc"));
+        assert!(
+            ExcludeRules {
+                patterns: "regex:(".to_owned(),
+                ..ExcludeRules::default()
+            }
+            .compile()
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn schemes_ignore_case_and_colons() {
+        let rules = rules("About:, file  moz-extension", "", "");
+        assert!(rules.matches("about:newtab"));
+        assert!(rules.matches("FILE:///home/a.html"));
+        assert!(rules.matches("moz-extension://abc/page.html"));
+        assert!(!rules.matches("https://about.com/"));
+    }
+
+    #[test]
+    fn domains_include_subdomains() {
+        let rules = rules("", "", "www.Example.com\n\n  tracker.net ");
+        assert!(rules.matches("https://example.com/"));
+        assert!(rules.matches("https://www.example.com/a"));
+        assert!(rules.matches("https://mail.example.com/"));
+        assert!(rules.matches("http://ads.tracker.net:8080/"));
+        assert!(!rules.matches("https://notexample.com/"));
+        assert!(!rules.matches("https://example.com.evil.org/"));
+    }
+}
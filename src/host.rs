@@ -4,7 +4,15 @@
 )]
 
 use std::{
-    borrow::Cow, env, future::Future, io::Empty, path::PathBuf, sync::Arc, time::SystemTime,
+    borrow::Cow,
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+    env,
+    future::Future,
+    io::Empty,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::SystemTime,
 };
 
 use either::Either;
@@ -13,6 +21,14 @@ use firefox_session_data::{find, session_store::FirefoxSessionStore};
 #[cfg(feature = "real_data")]
 pub use firefox_session_data::{snss, to_links::ttl_formats::FormatInfo};
 
+use crate::{
+    i18n::tr,
+    preview,
+    session::{self, NativeGroupId, SessionTabs, TabId, TabInfo, VirtualGroup},
+    statistics::{self, Statistics},
+    url_cleaning::UrlCleaning,
+};
+
 /// Unconditionally sendable when targeting the web.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct WebSendable<T>(pub T);
@@ -27,17 +43,16 @@ unsafe impl<T> Sync for WebSendable<T> {}
 /// A version of [`tokio::task::spawn_blocking`] that works for the WebAssembly
 /// target where we don't have access to threads, in that case we simply block
 /// the runtime (i.e. the event loop).
-#[cfg(feature = "real_data")]
 pub async fn spawn_blocking<F, R>(f: F) -> R
 where
     F: FnOnce() -> R + Send + 'static,
     R: Send + 'static,
 {
-    #[cfg(target_family = "wasm")]
+    #[cfg(any(target_family = "wasm", not(feature = "real_data")))]
     {
         f()
     }
-    #[cfg(not(target_family = "wasm"))]
+    #[cfg(all(not(target_family = "wasm"), feature = "real_data"))]
     {
         tokio::task::spawn_blocking(f).await.unwrap()
     }
@@ -90,6 +105,7 @@ mod fake {
 
         pub async fn parse_session_data(&mut self) -> Result<(), String> {
             self.data = Some(FileData::Parsed(Arc::new(())));
+            self.tabs = Some(Arc::new(SessionTabs::fake()));
             Ok(())
         }
 
@@ -102,15 +118,24 @@ mod fake {
                     TabGroup {
                         index: 0,
                         name: "Window 1".into(),
+                        tabs: None,
+                        window: Some(0),
+                        summary: TabGroupSummary::default(),
                     },
                     TabGroup {
                         index: 1,
                         name: "Window 2".into(),
+                        tabs: None,
+                        window: Some(1),
+                        summary: TabGroupSummary::default(),
                     },
                 ],
                 closed: vec![TabGroup {
                     index: 2,
                     name: "Closed window 1".into(),
+                    tabs: None,
+                    window: Some(0),
+                    summary: TabGroupSummary::default(),
                 }],
             })
         }
@@ -118,8 +143,15 @@ mod fake {
         pub async fn to_text_links(
             &self,
             generate_options: GenerateOptions,
+            format: FormatInfo,
         ) -> Result<String, String> {
-            Ok("http://www.example.com".to_string())
+            Ok(match format {
+                FormatInfo::Text => "http://www.example.com".to_string(),
+                FormatInfo::PDF | FormatInfo::Html => {
+                    "<h1>Window 1</h1>\n<ul><li><a href=\"http://www.example.com\">Example Domain</a></li></ul>"
+                        .to_string()
+                }
+            })
         }
 
         pub async fn save_links(
@@ -127,8 +159,31 @@ mod fake {
             save_path: PathBuf,
             generate_options: GenerateOptions,
             output_options: OutputOptions,
-        ) -> Result<(), String> {
-            Ok(())
+        ) -> Result<PathBuf, String> {
+            Ok(save_path)
+        }
+    }
+
+    pub fn file_extension(format: FormatInfo) -> &'static str {
+        match format {
+            FormatInfo::PDF => "pdf",
+            FormatInfo::Text => "txt",
+            FormatInfo::Html => "html",
+        }
+    }
+
+    pub fn preview_syntax(format: FormatInfo) -> preview::Syntax {
+        match format {
+            FormatInfo::Text => preview::Syntax::Plain,
+            FormatInfo::PDF | FormatInfo::Html => preview::Syntax::Html,
+        }
+    }
+
+    pub fn clipboard_link_format(clipboard: ClipboardFormat, output: FormatInfo) -> FormatInfo {
+        match clipboard {
+            ClipboardFormat::MatchOutput => output,
+            ClipboardFormat::PlainText | ClipboardFormat::Markdown => FormatInfo::Text,
+            ClipboardFormat::Html => FormatInfo::Html,
         }
     }
 }
@@ -335,13 +390,289 @@ pub fn prompt_save_file(
     builder.save_file()
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct TabGroup {
     /// The index of the tab group. Used to uniquely identify a group in a
     /// specific session.
     pub index: u32,
     /// Name of a tab group.
     pub name: String,
+    /// Tabs in a virtual group. `None` if the group is a window (or tab group)
+    /// in the session.
+    pub tabs: Option<Vec<TabId>>,
+    /// Index of the session window that this group lists, `None` for virtual
+    /// groups and for groups that aren't windows (like Panorama tab groups).
+    pub window: Option<u32>,
+    /// Info about the group's tabs, empty if the session doesn't have info
+    /// about individual tabs.
+    pub summary: TabGroupSummary,
+}
+
+/// Number of tab titles that [`TabGroupSummary::first_titles`] keeps.
+const SUMMARY_TITLES: usize = 5;
+
+/// Info about the tabs in a group that is shown in the side panel.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TabGroupSummary {
+    pub tab_count: usize,
+    pub pinned_count: usize,
+    /// Title of the tab that is shown in the window.
+    pub selected_title: Option<String>,
+    /// When a tab in the group was last accessed, in milliseconds since the
+    /// Unix epoch.
+    pub last_active: Option<i64>,
+    /// Titles of the first few tabs in the group.
+    pub first_titles: Vec<String>,
+}
+impl TabGroupSummary {
+    pub fn new<'a>(tabs: impl Iterator<Item = &'a TabInfo>) -> Self {
+        let mut summary = Self::default();
+        for tab in tabs {
+            summary.tab_count += 1;
+            summary.pinned_count += usize::from(tab.pinned);
+            if tab.selected && summary.selected_title.is_none() {
+                summary.selected_title = Some(tab.title.clone());
+            }
+            summary.last_active = summary.last_active.max(tab.last_accessed);
+            if summary.first_titles.len() < SUMMARY_TITLES {
+                summary.first_titles.push(tab.title.clone());
+            }
+        }
+        summary
+    }
+}
+
+impl TabGroup {
+    /// The tabs that belong to this group.
+    pub fn tabs<'a>(
+        &'a self,
+        tabs: &'a SessionTabs,
+        open: bool,
+    ) -> impl Iterator<Item = &'a TabInfo> + 'a {
+        match &self.tabs {
+            Some(ids) => Either::Left(ids.iter().filter_map(|id| tabs.tab(*id))),
+            None => Either::Right(
+                self.window
+                    .and_then(|window| tabs.window(open, window))
+                    .map(|window| window.tabs.as_slice())
+                    .unwrap_or_default()
+                    .iter(),
+            ),
+        }
+    }
+    /// `true` if the group holds the recently closed tabs of an open window.
+    /// Such groups are only exported when they are explicitly selected.
+    pub fn is_recently_closed(&self) -> bool {
+        self.tabs
+            .as_ref()
+            .and_then(|ids| ids.first())
+            .is_some_and(|id| id.recently_closed)
+    }
+}
+
+/// How tabs are grouped in the side panel and in exported links.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum TabGrouping {
+    /// Use the windows (or tab groups) that are stored in the session.
+    #[default]
+    Window,
+    /// Regroup all tabs by their domain.
+    Domain,
+    /// Regroup all tabs by their container.
+    Container,
+}
+impl TabGrouping {
+    pub fn all() -> &'static [Self] {
+        &[Self::Window, Self::Domain, Self::Container]
+    }
+    pub fn label(self) -> String {
+        match self {
+            TabGrouping::Window => tr!("grouping-window"),
+            TabGrouping::Domain => tr!("grouping-domain"),
+            TabGrouping::Container => tr!("grouping-container"),
+        }
+    }
+    /// Heading for the groups from either open or closed windows.
+    pub fn heading(self, open: bool) -> String {
+        match (self, open) {
+            (TabGrouping::Window, true) => tr!("heading-open-windows"),
+            (TabGrouping::Window, false) => tr!("heading-closed-windows"),
+            (TabGrouping::Domain, true) => tr!("heading-open-domains"),
+            (TabGrouping::Domain, false) => tr!("heading-closed-domains"),
+            (TabGrouping::Container, true) => tr!("heading-open-containers"),
+            (TabGrouping::Container, false) => tr!("heading-closed-containers"),
+        }
+    }
+    /// Virtual groups for tabs in either open or closed windows, `None` if the
+    /// session's own groups should be used.
+    pub fn virtual_groups(self, tabs: &SessionTabs, open: bool) -> Option<Vec<VirtualGroup>> {
+        match self {
+            TabGrouping::Window => None,
+            TabGrouping::Domain => Some(tabs.group_by(open, |tab| {
+                session::domain(&tab.url).unwrap_or_else(|| tr!("other-domain"))
+            })),
+            TabGrouping::Container => {
+                Some(tabs.group_by(open, |tab| tabs.containers.name(tab.user_context_id)))
+            }
+        }
+    }
+}
+
+/// Which pages from each tab's back/forward history are exported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum TabHistory {
+    /// Only the page that the tab currently shows.
+    #[default]
+    CurrentPage,
+    /// Every page in the tab's back/forward history.
+    FullHistory,
+    /// Every URL that was visited in the tab, without duplicates.
+    AllUrls,
+}
+impl TabHistory {
+    pub fn all() -> &'static [Self] {
+        &[Self::CurrentPage, Self::FullHistory, Self::AllUrls]
+    }
+    pub fn label(self) -> String {
+        match self {
+            TabHistory::CurrentPage => tr!("history-current-page"),
+            TabHistory::FullHistory => tr!("history-full"),
+            TabHistory::AllUrls => tr!("history-all-urls"),
+        }
+    }
+}
+
+/// Value that windows or tabs are sorted by, see [`WindowSort::key`] and
+/// [`TabSort::key`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SortKey {
+    /// Keep the order from the session.
+    Original,
+    Text(String),
+    /// Largest numbers first.
+    Descending(Reverse<i64>),
+}
+
+/// Order of windows (or other groups of tabs) in the side panel and in
+/// exported links.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum WindowSort {
+    /// Same order as in the session.
+    #[default]
+    Session,
+    Name,
+    /// Windows with the most tabs first.
+    TabCount,
+    /// Windows with the most recently accessed tab first.
+    LastActivity,
+}
+impl WindowSort {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Session,
+            Self::Name,
+            Self::TabCount,
+            Self::LastActivity,
+        ]
+    }
+    pub fn label(self) -> String {
+        match self {
+            WindowSort::Session => tr!("window-sort-session"),
+            WindowSort::Name => tr!("window-sort-name"),
+            WindowSort::TabCount => tr!("window-sort-tab-count"),
+            WindowSort::LastActivity => tr!("window-sort-last-activity"),
+        }
+    }
+    /// The value that a group with the specified name and tabs is sorted by.
+    pub fn key<'a>(self, name: &str, tabs: impl Iterator<Item = &'a TabInfo>) -> SortKey {
+        match self {
+            WindowSort::Session => SortKey::Original,
+            WindowSort::Name => SortKey::Text(name.to_lowercase()),
+            WindowSort::TabCount => SortKey::Descending(Reverse(tabs.count() as i64)),
+            WindowSort::LastActivity => SortKey::Descending(Reverse(
+                tabs.filter_map(|tab| tab.last_accessed)
+                    .max()
+                    .unwrap_or_default(),
+            )),
+        }
+    }
+}
+
+/// Order of tabs inside each window in the tab list and in exported links.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum TabSort {
+    /// Same order as in the tab strip.
+    #[default]
+    Position,
+    Title,
+    Domain,
+    /// Most recently accessed tabs first.
+    LastAccessed,
+}
+impl TabSort {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Position,
+            Self::Title,
+            Self::Domain,
+            Self::LastAccessed,
+        ]
+    }
+    pub fn label(self) -> String {
+        match self {
+            TabSort::Position => tr!("tab-sort-position"),
+            TabSort::Title => tr!("tab-sort-title"),
+            TabSort::Domain => tr!("tab-sort-domain"),
+            TabSort::LastAccessed => tr!("tab-sort-last-accessed"),
+        }
+    }
+    /// The value that a tab is sorted by.
+    pub fn key(self, tab: &TabInfo) -> SortKey {
+        match self {
+            TabSort::Position => SortKey::Original,
+            TabSort::Title => SortKey::Text(tab.title.to_lowercase()),
+            TabSort::Domain => SortKey::Text(session::domain(&tab.url).unwrap_or_default()),
+            TabSort::LastAccessed => {
+                SortKey::Descending(Reverse(tab.last_accessed.unwrap_or_default()))
+            }
+        }
+    }
+}
+
+/// Groups for the recently closed tabs of each open window. These are listed
+/// after the session's own groups, so their indexes start after the last
+/// window in `window_names`.
+pub fn recently_closed_groups(tabs: &SessionTabs, window_names: &[String]) -> Vec<TabGroup> {
+    let windows = (0_u32..)
+        .zip(&tabs.open)
+        .filter(|(_, window)| !window.closed_tabs.is_empty());
+    (window_names.len() as u32..)
+        .zip(windows)
+        .map(|(index, (window, info))| {
+            let name = tabs
+                .window_of_group(true, window)
+                .and_then(|index| window_names.get(index as usize))
+                .cloned()
+                .unwrap_or_else(|| tr!("window-number", number = window + 1));
+            TabGroup {
+                index,
+                name: tr!("closed-tabs-from", name = name),
+                tabs: Some(info.closed_tabs.iter().map(|tab| tab.id).collect()),
+                window: None,
+                summary: TabGroupSummary::default(),
+            }
+        })
+        .collect()
+}
+
+/// Identifies a window in a session, used to remember custom window names.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Deserialize, serde::Serialize,
+)]
+pub struct WindowKey {
+    /// `true` for open windows, `false` for recently closed windows.
+    pub open: bool,
+    pub index: u32,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -351,6 +682,48 @@ pub struct AllTabGroups {
     /// Tab groups or windows in recently closed windows.
     pub closed: Vec<TabGroup>,
 }
+impl AllTabGroups {
+    /// The group that lists a window of the session.
+    pub fn group_of_window(&self, open: bool, window: u32) -> Option<&TabGroup> {
+        if open { &self.open } else { &self.closed }
+            .iter()
+            .find(|group| group.window == Some(window))
+    }
+    /// Fill in [`TabGroup::summary`] for every group.
+    pub fn update_summaries(&mut self, tabs: &SessionTabs) {
+        for (open, groups) in [(true, &mut self.open), (false, &mut self.closed)] {
+            for group in groups {
+                group.summary = TabGroupSummary::new(group.tabs(tabs, open));
+            }
+        }
+    }
+    /// Reorder the groups, their indexes still refer to the same groups.
+    /// Groups of recently closed tabs are kept after the windows.
+    ///
+    /// `names` are custom names for some of the windows.
+    pub fn sort(
+        &mut self,
+        tabs: Option<&SessionTabs>,
+        sort: WindowSort,
+        names: &BTreeMap<WindowKey, String>,
+    ) {
+        for (open, groups) in [(true, &mut self.open), (false, &mut self.closed)] {
+            groups.sort_by_cached_key(|group| {
+                let name = names
+                    .get(&WindowKey {
+                        open,
+                        index: group.index,
+                    })
+                    .unwrap_or(&group.name);
+                let key = match tabs {
+                    Some(tabs) => sort.key(name, group.tabs(tabs, open)),
+                    None => sort.key(name, std::iter::empty()),
+                };
+                (group.is_recently_closed(), key, group.index)
+            });
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GenerateOptions {
@@ -358,12 +731,75 @@ pub struct GenerateOptions {
     pub closed_group_indexes: Option<Vec<u32>>,
     pub sort_groups: bool,
     pub table_of_content: bool,
+    /// Indent tabs below their parent tab using tree data from Sidebery or
+    /// Tree Style Tab.
+    pub tab_tree: bool,
+    /// Tabs that should be left out when converting the session to links.
+    pub excluded_tabs: BTreeSet<TabId>,
+    /// Tabs that are left out because they match the exclude rules.
+    pub rule_excluded_tabs: BTreeSet<TabId>,
+    /// The group indexes refer to these groups.
+    pub grouping: TabGrouping,
+    /// Include the container of each tab in its title.
+    pub container_labels: bool,
+    /// Pages from each tab's history that should be included.
+    pub history: TabHistory,
+    /// Changes made to the URL of every tab.
+    pub url_cleaning: UrlCleaning,
+    /// Order of the exported windows. This is applied on top of
+    /// `sort_groups`, which `firefox_session_data` uses for tab groups.
+    pub window_sort: WindowSort,
+    /// Order of the exported tabs inside each window.
+    pub tab_sort: TabSort,
+    /// Names chosen by the user that replace the session's window names.
+    /// Only used when grouping tabs by window.
+    pub window_names: BTreeMap<WindowKey, String>,
+    /// Native tab groups that are left out even though their window is
+    /// selected. Only used when grouping tabs by window.
+    pub excluded_native_groups: BTreeSet<NativeGroupId>,
 }
 impl GenerateOptions {
+    /// Check if a tab is left out because its native tab group isn't selected.
+    pub fn is_in_excluded_native_group(&self, tab: &TabInfo) -> bool {
+        self.grouping == TabGrouping::Window
+            && !tab.id.recently_closed
+            && !self.excluded_native_groups.is_empty()
+            && self
+                .excluded_native_groups
+                .contains(&NativeGroupId::of_tab(tab))
+    }
+    /// Tabs that are excluded directly, by an exclude rule or because of
+    /// their native tab group.
+    pub fn all_excluded_tabs<'a>(&'a self, tabs: &SessionTabs) -> Cow<'a, BTreeSet<TabId>> {
+        let native_groups =
+            self.grouping == TabGrouping::Window && !self.excluded_native_groups.is_empty();
+        if !native_groups && self.rule_excluded_tabs.is_empty() {
+            return Cow::Borrowed(&self.excluded_tabs);
+        }
+        let mut excluded = self.excluded_tabs.clone();
+        excluded.extend(&self.rule_excluded_tabs);
+        excluded.extend(
+            tabs.open
+                .iter()
+                .chain(&tabs.closed)
+                .flat_map(|window| &window.tabs)
+                .filter(|tab| self.is_in_excluded_native_group(tab))
+                .map(|tab| tab.id),
+        );
+        Cow::Owned(excluded)
+    }
     pub fn selected_groups(&self) -> usize {
         self.open_group_indexes.as_ref().map_or(0, Vec::len)
             + self.closed_group_indexes.as_ref().map_or(0, Vec::len)
     }
+    /// Check if the user deselected every group, then nothing is exported.
+    pub fn selects_nothing(&self) -> bool {
+        self.open_group_indexes.as_ref().is_some_and(Vec::is_empty)
+            && self
+                .closed_group_indexes
+                .as_ref()
+                .is_some_and(Vec::is_empty)
+    }
 }
 impl Default for GenerateOptions {
     fn default() -> Self {
@@ -372,6 +808,17 @@ impl Default for GenerateOptions {
             closed_group_indexes: Some(Vec::new()),
             sort_groups: true,
             table_of_content: true,
+            tab_tree: true,
+            excluded_tabs: BTreeSet::new(),
+            rule_excluded_tabs: BTreeSet::new(),
+            grouping: TabGrouping::Window,
+            container_labels: false,
+            history: TabHistory::CurrentPage,
+            url_cleaning: UrlCleaning::default(),
+            window_sort: WindowSort::Session,
+            tab_sort: TabSort::Position,
+            window_names: BTreeMap::new(),
+            excluded_native_groups: BTreeSet::new(),
         }
     }
 }
@@ -381,6 +828,9 @@ pub struct OutputOptions {
     pub format: FormatInfo,
     pub overwrite: bool,
     pub create_folder: bool,
+    /// Append a number to the file name if a file already exists at the save
+    /// path.
+    pub auto_increment: bool,
 }
 
 impl Default for OutputOptions {
@@ -389,10 +839,345 @@ impl Default for OutputOptions {
             format: FormatInfo::PDF,
             overwrite: Default::default(),
             create_folder: Default::default(),
+            auto_increment: Default::default(),
+        }
+    }
+}
+
+/// Get the groups that the selected tabs should be exported as, `None` if
+/// the session's own groups can be used.
+#[cfg(feature = "real_data")]
+fn export_groups(
+    session: &FirefoxSessionStore,
+    tabs: &SessionTabs,
+    options: &GenerateOptions,
+) -> Option<(Vec<VirtualGroup>, Vec<VirtualGroup>)> {
+    use firefox_session_data::session_store::session_info::get_groups_from_session;
+
+    let is_selected = |open: bool, index: u32| {
+        if open {
+            &options.open_group_indexes
+        } else {
+            &options.closed_group_indexes
+        }
+        .as_ref()
+        .is_none_or(|indexes| indexes.contains(&index))
+    };
+
+    if let (Some(open), Some(closed)) = (
+        options.grouping.virtual_groups(tabs, true),
+        options.grouping.virtual_groups(tabs, false),
+    ) {
+        let selected = |open: bool, groups: Vec<VirtualGroup>| {
+            let mut groups = (0_u32..)
+                .zip(groups)
+                .filter(|(index, _)| is_selected(open, *index))
+                .map(|(_, group)| group)
+                .collect::<Vec<_>>();
+            groups.sort_by_cached_key(|group| {
+                let group_tabs = group.tabs.iter().filter_map(|id| tabs.tab(*id));
+                options.window_sort.key(&group.name, group_tabs)
+            });
+            groups
+        };
+        return Some((selected(true, open), selected(false, closed)));
+    }
+
+    // The sections below are written per window, which the session's groups
+    // can't be mapped to if they are Panorama tab groups:
+    if tabs.has_panorama_groups() {
+        return None;
+    }
+
+    let window_names = |open: bool| {
+        (0_u32..)
+            .zip(get_groups_from_session(
+                session,
+                open,
+                !open,
+                options.sort_groups,
+            ))
+            .map(|(index, group)| {
+                options
+                    .window_names
+                    .get(&WindowKey { open, index })
+                    .cloned()
+                    .unwrap_or_else(|| group.name().to_owned())
+            })
+            .collect::<Vec<_>>()
+    };
+    let open_names = window_names(true);
+    // Recently closed tabs are only exported if their group is explicitly
+    // selected:
+    let closed_tabs = recently_closed_groups(tabs, &open_names)
+        .into_iter()
+        .filter(|group| {
+            options
+                .open_group_indexes
+                .as_ref()
+                .is_some_and(|indexes| indexes.contains(&group.index))
+        })
+        .map(|group| VirtualGroup {
+            name: group.name,
+            tabs: group.tabs.unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+
+    // Windows with native tab groups get a section for each tab group:
+    let selected_windows = |open: bool| {
+        (0_u32..)
+            .zip(if open { &tabs.open } else { &tabs.closed })
+            .filter(move |(index, _)| is_selected(open, *index))
+    };
+    // Custom window names can only be used if we write the sections ourselves:
+    let renamed = options
+        .window_names
+        .keys()
+        .any(|key| is_selected(key.open, key.index));
+    if closed_tabs.is_empty()
+        && !renamed
+        && options.window_sort == WindowSort::Session
+        && !selected_windows(true)
+            .chain(selected_windows(false))
+            .any(|(_, window)| !window.groups.is_empty())
+    {
+        return None;
+    }
+    let window_name = |names: &[String], index: u32| {
+        names
+            .get(index as usize)
+            .cloned()
+            .unwrap_or_else(|| tr!("window-number", number = index + 1))
+    };
+    let sections = |open: bool, names: &[String]| {
+        let mut windows = selected_windows(open).collect::<Vec<_>>();
+        windows.sort_by_cached_key(|(index, window)| {
+            let name = window_name(names, *index);
+            options.window_sort.key(&name, window.tabs.iter())
+        });
+        windows
+            .into_iter()
+            .flat_map(|(index, window)| {
+                let name = window_name(names, index);
+                let ungrouped = VirtualGroup {
+                    name: name.clone(),
+                    tabs: window
+                        .tabs
+                        .iter()
+                        .filter(|tab| tab.group_id.is_none())
+                        .map(|tab| tab.id)
+                        .collect(),
+                };
+                let groups = window.groups.iter().map(move |group| VirtualGroup {
+                    name: format!("{name}: {}", group.name),
+                    tabs: group.tabs.clone(),
+                });
+                std::iter::once(ungrouped).chain(groups)
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut open = sections(true, &open_names);
+    open.extend(closed_tabs);
+    Some((open, sections(false, &window_names(false))))
+}
+
+/// A session that was modified by [`session_for_export`], kept so that
+/// previews in other formats don't have to modify the session again.
+#[cfg(feature = "real_data")]
+#[derive(Debug)]
+pub struct ExportedSession {
+    /// The options that the session was modified for.
+    key: GenerateOptions,
+    /// The options after [`session_for_export`] changed them.
+    options: GenerateOptions,
+    session: Arc<FirefoxSessionStore>,
+}
+
+/// Where the tab tree is read from. The first source that has data is used,
+/// so if there is any data from Sidebery then TST data won't be used at all.
+#[cfg(feature = "real_data")]
+fn tree_sources(
+    options: &GenerateOptions,
+) -> &'static [firefox_session_data::session_store::session_info::TreeDataSource] {
+    use firefox_session_data::session_store::session_info::TreeDataSource;
+
+    if options.tab_tree {
+        &[
+            TreeDataSource::Sidebery,
+            TreeDataSource::TstWebExtension,
+            TreeDataSource::TstLegacy,
+        ]
+    } else {
+        &[]
+    }
+}
+
+/// Get the session that should be converted to links, taking into account
+/// options that require modifying the session data.
+///
+/// If the tabs are regrouped then the returned session only contains the
+/// selected groups and `options` is changed to select all of them.
+#[cfg(feature = "real_data")]
+fn session_for_export(
+    session: &Arc<FirefoxSessionStore>,
+    tabs: Option<&SessionTabs>,
+    options: &mut GenerateOptions,
+    cache: &Mutex<Option<ExportedSession>>,
+) -> Result<Arc<FirefoxSessionStore>, String> {
+    let Some(tabs) = tabs else {
+        return Ok(Arc::clone(session));
+    };
+    let key = GenerateOptions {
+        // Only affect the conversion to links:
+        table_of_content: false,
+        tab_tree: false,
+        ..options.clone()
+    };
+    if let Ok(cache) = cache.lock()
+        && let Some(cached) = cache.as_ref().filter(|cached| cached.key == key)
+    {
+        *options = cached.options.clone();
+        return Ok(Arc::clone(&cached.session));
+    }
+    let excluded = options.all_excluded_tabs(tabs);
+    let mut json = if let Some((open, closed)) = export_groups(session, tabs, options) {
+        let json = tabs.json_with_groups(&open, &closed, &excluded)?;
+        options.open_group_indexes = None;
+        options.closed_group_indexes = None;
+        json
+    } else if !excluded.is_empty()
+        || options.container_labels
+        || options.history != TabHistory::CurrentPage
+        || options.url_cleaning.is_enabled()
+        || options.tab_sort != TabSort::Position
+    {
+        tabs.json_without_tabs(&excluded)?
+    } else {
+        return Ok(Arc::clone(session));
+    };
+    if options.container_labels {
+        tabs.add_container_labels(&mut json);
+    }
+    // Clean URLs first so that duplicates are found after cleaning:
+    if options.url_cleaning.is_enabled() {
+        SessionTabs::clean_urls(&mut json, options.url_cleaning);
+    }
+    if options.tab_sort != TabSort::Position {
+        SessionTabs::sort_tabs(&mut json, |tab| options.tab_sort.key(tab));
+    }
+    match options.history {
+        TabHistory::CurrentPage => {}
+        TabHistory::FullHistory => SessionTabs::expand_history(&mut json, false),
+        TabHistory::AllUrls => SessionTabs::expand_history(&mut json, true),
+    }
+    let modified = serde_json::from_value(json)
+        .map(Arc::new)
+        .map_err(|e| tr!("error-modify-session", error = e))?;
+    if let Ok(mut cache) = cache.lock() {
+        *cache = Some(ExportedSession {
+            key,
+            options: options.clone(),
+            session: Arc::clone(&modified),
+        });
+    }
+    Ok(modified)
+}
+
+/// The file extension that should be used for files with the specified format.
+#[cfg(feature = "real_data")]
+pub fn file_extension(format: FormatInfo) -> &'static str {
+    use firefox_session_data::session_store::to_links::LinkFormat;
+
+    let (format, as_pdf) = format.as_format().to_link_format();
+    if as_pdf.is_some() {
+        "pdf"
+    } else {
+        match format {
+            LinkFormat::TXT => "txt",
+            LinkFormat::RTF { .. } => "rtf",
+            LinkFormat::HTML => "html",
+            LinkFormat::Markdown => "md",
+            LinkFormat::Typst => "typ",
+        }
+    }
+}
+
+/// File extensions of all output formats.
+pub fn file_extensions() -> Vec<&'static str> {
+    FormatInfo::all()
+        .iter()
+        .map(|&format| file_extension(format))
+        .collect()
+}
+
+/// The markup language of the links that [`FileInfo::to_text_links`]
+/// generates for the specified format.
+#[cfg(feature = "real_data")]
+pub fn preview_syntax(format: FormatInfo) -> preview::Syntax {
+    use firefox_session_data::session_store::to_links::LinkFormat;
+
+    match format.as_format().to_link_format().0 {
+        LinkFormat::HTML => preview::Syntax::Html,
+        LinkFormat::Markdown => preview::Syntax::Markdown,
+        LinkFormat::TXT | LinkFormat::RTF { .. } | LinkFormat::Typst => preview::Syntax::Plain,
+    }
+}
+
+/// The format that links are copied to the clipboard in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum ClipboardFormat {
+    /// Use the same format as the selected output format.
+    #[default]
+    MatchOutput,
+    PlainText,
+    Markdown,
+    Html,
+}
+impl ClipboardFormat {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::MatchOutput,
+            Self::PlainText,
+            Self::Markdown,
+            Self::Html,
+        ]
+    }
+    pub fn label(self) -> String {
+        match self {
+            ClipboardFormat::MatchOutput => tr!("clipboard-match-output"),
+            ClipboardFormat::PlainText => tr!("clipboard-plain-text"),
+            ClipboardFormat::Markdown => tr!("clipboard-markdown"),
+            ClipboardFormat::Html => tr!("clipboard-html"),
         }
     }
 }
 
+/// The format that should be used to generate links that are copied to the
+/// clipboard.
+#[cfg(feature = "real_data")]
+pub fn clipboard_link_format(clipboard: ClipboardFormat, output: FormatInfo) -> FormatInfo {
+    use firefox_session_data::session_store::to_links::LinkFormat;
+
+    let wanted = match clipboard {
+        ClipboardFormat::MatchOutput => return output,
+        ClipboardFormat::PlainText => LinkFormat::TXT,
+        ClipboardFormat::Markdown => LinkFormat::Markdown,
+        ClipboardFormat::Html => LinkFormat::HTML,
+    };
+    find_link_format(wanted).unwrap_or(output)
+}
+
+/// Find an output format that writes the specified link format directly.
+#[cfg(feature = "real_data")]
+fn find_link_format(
+    wanted: firefox_session_data::session_store::to_links::LinkFormat,
+) -> Option<FormatInfo> {
+    FormatInfo::all().iter().copied().find(|format| {
+        let (format, as_pdf) = format.as_format().to_link_format();
+        as_pdf.is_none() && std::mem::discriminant(&format) == std::mem::discriminant(&wanted)
+    })
+}
+
 #[derive(Debug, Clone)]
 pub enum FileData {
     Chromium(Arc<snss::SessionStore>),
@@ -415,6 +1200,12 @@ pub struct FileInfo {
     pub file_path: Arc<PathBuf>,
     pub file_handle: Option<WebSendable<rfd::FileHandle>>,
     pub data: Option<FileData>,
+    /// Info about individual tabs, only available for Firefox sessions.
+    pub tabs: Option<Arc<SessionTabs>>,
+    /// The latest session that was modified before it was converted to
+    /// links.
+    #[cfg(feature = "real_data")]
+    exported: Arc<Mutex<Option<ExportedSession>>>,
 }
 impl FileInfo {
     pub fn new(file_path: PathBuf) -> Self {
@@ -422,6 +1213,9 @@ impl FileInfo {
             file_path: Arc::new(file_path),
             file_handle: None,
             data: None,
+            tabs: None,
+            #[cfg(feature = "real_data")]
+            exported: Arc::default(),
         }
     }
     pub fn is_compressed_file_format(&self) -> bool {
@@ -431,6 +1225,55 @@ impl FileInfo {
             .unwrap_or(false)
     }
 }
+impl FileInfo {
+    /// List the groups that tabs are divided into, either the session's own
+    /// windows or virtual groups. Sessions without info about individual tabs
+    /// (i.e. Chromium sessions) always use their windows.
+    pub async fn tab_groups(&self, grouping: TabGrouping) -> Result<AllTabGroups, String> {
+        let Some(tabs) = self.tabs.clone() else {
+            return self.get_groups_from_session(true).await;
+        };
+        if grouping == TabGrouping::Window {
+            let mut groups = self.get_groups_from_session(true).await?;
+            let names = groups
+                .open
+                .iter()
+                .map(|group| group.name.clone())
+                .collect::<Vec<_>>();
+            groups.open.extend(recently_closed_groups(&tabs, &names));
+            groups.update_summaries(&tabs);
+            return Ok(groups);
+        }
+        let groups = |open: bool| {
+            (0..)
+                .zip(grouping.virtual_groups(&tabs, open).unwrap_or_default())
+                .map(|(index, group)| TabGroup {
+                    index,
+                    name: group.name,
+                    tabs: Some(group.tabs),
+                    window: None,
+                    summary: TabGroupSummary::default(),
+                })
+                .collect()
+        };
+        let mut groups = AllTabGroups {
+            open: groups(true),
+            closed: groups(false),
+        };
+        groups.update_summaries(&tabs);
+        Ok(groups)
+    }
+
+    /// Calculate statistics about the session's tabs.
+    pub async fn statistics(&self, groups: AllTabGroups) -> Result<Statistics, String> {
+        let tabs = self
+            .tabs
+            .clone()
+            .ok_or_else(|| tr!("error-statistics-unavailable"))?;
+
+        Ok(spawn_blocking(move || statistics::compute(&tabs, &groups)).await)
+    }
+}
 #[cfg(feature = "real_data")]
 impl FileInfo {
     pub async fn load_data(&mut self) -> Result<(), String> {
@@ -472,14 +1315,14 @@ impl FileInfo {
                     )));
                 }
                 let file = File::open(&*path)
-                    .map_err(|e| format!("failed to open file at {}: {e}", path.display()))?;
+                    .map_err(|e| tr!("error-open-file", path = path.display(), error = e))?;
 
                 let mut buffer = BufReader::new(file);
                 let mut data = Vec::new();
 
-                buffer.read_to_end(&mut data).map_err(|e| {
-                    format!("failed to read file data from {}: {e}", path.display())
-                })?;
+                buffer
+                    .read_to_end(&mut data)
+                    .map_err(|e| tr!("error-read-file", path = path.display(), error = e))?;
 
                 let data = Arc::from(data);
                 Ok(if is_compressed {
@@ -510,7 +1353,7 @@ impl FileInfo {
                 Vec::<u8>::from(&*data).into(),
             ))
             .map(|reader| -> Vec<u8> { reader.into() })
-            .map_err(|e| format!("failed to decompress data: {e}"))
+            .map_err(|e| tr!("error-decompress", error = e))
         })
         .await?;
 
@@ -528,13 +1371,27 @@ impl FileInfo {
             FileData::Parsed(_) => return Ok(()),
             FileData::Chromium(_) => return Ok(()),
         };
-        let session = spawn_blocking(move || {
-            serde_json::from_slice::<FirefoxSessionStore>(&data)
-                .map_err(|e| format!("failed to parse sessionstore JSON data: {e}"))
+        #[cfg(not(target_family = "wasm"))]
+        let path = self.file_path.clone();
+        let (session, tabs) = spawn_blocking(move || {
+            let session = serde_json::from_slice::<FirefoxSessionStore>(&data)
+                .map_err(|e| tr!("error-parse-json", error = e))?;
+            #[cfg_attr(target_family = "wasm", expect(unused_mut))]
+            let mut tabs = SessionTabs::from_json(data)?;
+
+            // Container names are optional so just ignore any errors:
+            #[cfg(not(target_family = "wasm"))]
+            match crate::containers::Containers::load_for_session(&path) {
+                Ok(containers) => tabs.containers = containers,
+                Err(e) => log::debug!("No container info: {e}"),
+            }
+
+            Ok::<_, String>((session, tabs))
         })
         .await?;
 
         self.data = Some(FileData::Parsed(Arc::new(session)));
+        self.tabs = Some(Arc::new(tabs));
         Ok(())
     }
     pub async fn get_groups_from_session(&self, sort_groups: bool) -> Result<AllTabGroups, String> {
@@ -545,7 +1402,7 @@ impl FileInfo {
                 .sources()
                 .iter()
                 .find(|source| source.kind == snss::SourceKind::Last)
-                .ok_or("Could not find latest Chromium session file")?;
+                .ok_or_else(|| tr!("error-no-chromium-session"))?;
             return Ok(AllTabGroups {
                 open: latest
                     .windows
@@ -553,7 +1410,10 @@ impl FileInfo {
                     .enumerate()
                     .map(|(index, _window)| TabGroup {
                         index: index as u32,
-                        name: format!("Window {}", index + 1),
+                        name: tr!("window-number", number = index + 1),
+                        tabs: None,
+                        window: Some(index as u32),
+                        summary: TabGroupSummary::default(),
                     })
                     .collect(),
                 closed: Vec::new(),
@@ -566,56 +1426,57 @@ impl FileInfo {
             .and_then(FileData::as_parsed)
             .cloned()
             .ok_or("must deserialize JSON sessionstore data before tab groups can be inspected")?;
+        let tabs = self.tabs.clone();
 
-        Ok(spawn_blocking(move || AllTabGroups {
-            open: get_groups_from_session(&session, true, false, sort_groups)
-                .enumerate()
-                .map(|(ix, group)| TabGroup {
-                    index: ix as _,
-                    name: group.name().to_owned(),
-                })
-                .collect::<Vec<_>>(),
-            closed: get_groups_from_session(&session, false, true, sort_groups)
-                .enumerate()
-                .map(|(ix, group)| TabGroup {
-                    index: ix as _,
-                    name: group.name().to_owned(),
-                })
-                .collect::<Vec<_>>(),
+        Ok(spawn_blocking(move || {
+            let groups = |open: bool| {
+                get_groups_from_session(&session, open, !open, sort_groups)
+                    .enumerate()
+                    .map(|(ix, group)| TabGroup {
+                        index: ix as _,
+                        name: group.name().to_owned(),
+                        tabs: None,
+                        window: tabs
+                            .as_ref()
+                            .and_then(|tabs| tabs.window_of_group(open, ix as _)),
+                        summary: TabGroupSummary::default(),
+                    })
+                    .collect::<Vec<_>>()
+            };
+            AllTabGroups {
+                open: groups(true),
+                closed: groups(false),
+            }
         })
         .await)
     }
 
-    /// Generate a text only representation of the sessionstore data.
-    pub async fn to_text_links(&self, generate_options: GenerateOptions) -> Result<String, String> {
+    /// Generate a text representation of the sessionstore data in the
+    /// specified format. For PDF formats this is the source that would be
+    /// converted into a PDF.
+    pub async fn to_text_links(
+        &self,
+        generate_options: GenerateOptions,
+        format: FormatInfo,
+    ) -> Result<String, String> {
         use firefox_session_data::{
             pdf_converter::html_to_pdf::WriteBuilderSimple,
-            session_store::{
-                session_info::{TreeDataSource, get_groups_from_session},
-                to_links::LinkFormat,
-                to_links::ToLinksOptions,
-            },
+            session_store::{session_info::get_groups_from_session, to_links::ToLinksOptions},
             to_links::TabsToLinksOutput,
         };
 
+        let (format, _as_pdf) = format.as_format().to_link_format();
         let options = TabsToLinksOutput {
-            format: LinkFormat::TXT,
+            format,
             as_pdf: None,
             conversion_options: ToLinksOptions {
-                format: LinkFormat::TXT,
-                page_breaks_after_group: false, // We don't have any page break character in raw text
+                format,
+                page_breaks_after_group: false, // Page breaks aren't useful in a preview
                 skip_page_break_after_last_group: true,
                 table_of_contents: generate_options.table_of_content,
                 indent_all_links: true,
                 custom_page_break: "".into(),
-                // If there is any data from Sidebery then TST data
-                // won't be used and so on:
-                tree_sources: (&[
-                    TreeDataSource::Sidebery,
-                    TreeDataSource::TstWebExtension,
-                    TreeDataSource::TstLegacy,
-                ] as &[_])
-                    .into(),
+                tree_sources: tree_sources(&generate_options).into(),
             },
         };
 
@@ -627,7 +1488,7 @@ impl FileInfo {
                     .sources()
                     .iter()
                     .find(|source| source.kind == snss::SourceKind::Last)
-                    .ok_or("Could not find latest Chromium session file")?;
+                    .ok_or_else(|| tr!("error-no-chromium-session"))?;
 
                 let mut output: Vec<u8> = Vec::new();
 
@@ -663,8 +1524,13 @@ impl FileInfo {
             .and_then(FileData::as_parsed)
             .cloned()
             .ok_or("must deserialize JSON sessionstore data before converting tabs to links")?;
+        let tabs = self.tabs.clone();
+        let exported = Arc::clone(&self.exported);
 
         spawn_blocking(move || {
+            let mut generate_options = generate_options;
+            let session =
+                session_for_export(&session, tabs.as_deref(), &mut generate_options, &exported)?;
             let mut output: Vec<u8> = Vec::new();
 
             let open_groups =
@@ -702,20 +1568,17 @@ impl FileInfo {
         })
         .await
     }
-    #[cfg_attr(target_family = "wasm", expect(unused_mut, unused_variables))]
+    /// Write links to a file and return the path that was actually written to.
+    #[cfg_attr(target_family = "wasm", expect(unused_mut))]
     pub async fn save_links(
         &self,
         mut save_path: PathBuf,
         generate_options: GenerateOptions,
         output_options: OutputOptions,
-    ) -> Result<(), String> {
+    ) -> Result<PathBuf, String> {
         use firefox_session_data::{
             pdf_converter::html_to_pdf::WriteBuilderSimple,
-            session_store::{
-                session_info::{TreeDataSource, get_groups_from_session},
-                to_links::LinkFormat,
-                to_links::ToLinksOptions,
-            },
+            session_store::{session_info::get_groups_from_session, to_links::ToLinksOptions},
             to_links::TabsToLinksOutput,
         };
 
@@ -729,20 +1592,13 @@ impl FileInfo {
             }
         };
 
+        let tabs = self.tabs.clone();
+        let exported = Arc::clone(&self.exported);
+
         spawn_blocking(move || {
             let (format, as_pdf) = output_options.format.as_format().to_link_format();
-
-            let file_ext = if as_pdf.is_some() {
-                "pdf"
-            } else {
-                match format {
-                    LinkFormat::TXT => "txt",
-                    LinkFormat::RTF { .. } => "rtf",
-                    LinkFormat::HTML => "html",
-                    LinkFormat::Markdown => "md",
-                    LinkFormat::Typst => "typ",
-                }
-            };
+            let file_ext = file_extension(output_options.format);
+            save_path = crate::save_path::with_extension(save_path, file_ext, &file_extensions());
 
             let mut file = {
                 #[cfg(target_family = "wasm")]
@@ -751,15 +1607,17 @@ impl FileInfo {
                 }
                 #[cfg(not(target_family = "wasm"))]
                 {
-                    if save_path.extension().is_none() {
-                        save_path.set_extension(file_ext);
+                    if output_options.auto_increment {
+                        save_path = crate::save_path::next_free_path(&save_path).map_err(|e| {
+                            tr!("error-create-file", path = save_path.display(), error = e)
+                        })?;
                     }
 
                     if let Some(folder) = save_path.parent()
                         && output_options.create_folder
                     {
                         std::fs::create_dir_all(folder).map_err(|e| {
-                            format!("failed to create folder at \"{}\": {e}", folder.display())
+                            tr!("error-create-folder", path = folder.display(), error = e)
                         })?;
                     }
 
@@ -770,10 +1628,7 @@ impl FileInfo {
                         .create_new(!output_options.overwrite)
                         .open(&save_path)
                         .map_err(|e| {
-                            format!(
-                                "failed to create new file at \"{}\": {e}",
-                                save_path.display()
-                            )
+                            tr!("error-create-file", path = save_path.display(), error = e)
                         })?
                 }
             };
@@ -790,42 +1645,50 @@ impl FileInfo {
                     table_of_contents: generate_options.table_of_content,
                     indent_all_links: true,
                     custom_page_break: "".into(),
-                    // First found data is used so if there is any data from
-                    // Sidebery then TST data won't be used at all:
-                    tree_sources: (&[
-                        TreeDataSource::Sidebery,
-                        TreeDataSource::TstWebExtension,
-                        TreeDataSource::TstLegacy,
-                    ] as &[_])
-                        .into(),
+                    tree_sources: tree_sources(&generate_options).into(),
                 },
             };
 
             match &data {
                 FileData::Parsed(session) => {
-                    let open_groups =
-                        get_groups_from_session(session, true, false, generate_options.sort_groups)
-                            .enumerate()
-                            .filter(|(ix, _)| {
-                                if let Some(indexes) = &generate_options.open_group_indexes {
-                                    indexes.contains(&(*ix as u32))
-                                } else {
-                                    true
-                                }
-                            })
-                            .map(|(_, g)| g);
+                    let mut generate_options = generate_options;
+                    let session = session_for_export(
+                        session,
+                        tabs.as_deref(),
+                        &mut generate_options,
+                        &exported,
+                    )?;
+                    let open_groups = get_groups_from_session(
+                        &session,
+                        true,
+                        false,
+                        generate_options.sort_groups,
+                    )
+                    .enumerate()
+                    .filter(|(ix, _)| {
+                        if let Some(indexes) = &generate_options.open_group_indexes {
+                            indexes.contains(&(*ix as u32))
+                        } else {
+                            true
+                        }
+                    })
+                    .map(|(_, g)| g);
 
-                    let closed_groups =
-                        get_groups_from_session(session, false, true, generate_options.sort_groups)
-                            .enumerate()
-                            .filter(|(ix, _)| {
-                                if let Some(indexes) = &generate_options.closed_group_indexes {
-                                    indexes.contains(&(*ix as u32))
-                                } else {
-                                    true
-                                }
-                            })
-                            .map(|(_, g)| g);
+                    let closed_groups = get_groups_from_session(
+                        &session,
+                        false,
+                        true,
+                        generate_options.sort_groups,
+                    )
+                    .enumerate()
+                    .filter(|(ix, _)| {
+                        if let Some(indexes) = &generate_options.closed_group_indexes {
+                            indexes.contains(&(*ix as u32))
+                        } else {
+                            true
+                        }
+                    })
+                    .map(|(_, g)| g);
 
                     firefox_session_data::tabs_to_links(
                         &open_groups.chain(closed_groups).collect::<Vec<_>>(),
@@ -839,7 +1702,7 @@ impl FileInfo {
                         .sources()
                         .iter()
                         .find(|source| source.kind == snss::SourceKind::Last)
-                        .ok_or("Could not find latest Chromium session file")?;
+                        .ok_or_else(|| tr!("error-no-chromium-session"))?;
 
                     firefox_session_data::tabs_to_links(
                         &latest
@@ -866,9 +1729,15 @@ impl FileInfo {
             };
 
             #[cfg(target_family = "wasm")]
-            save_file_on_web_target(file.as_slice(), Some(&format!("firefox-links.{file_ext}")))?;
+            {
+                let name = save_path.file_name().map_or_else(
+                    || format!("firefox-links.{file_ext}"),
+                    |name| name.to_string_lossy().into_owned(),
+                );
+                save_file_on_web_target(file.as_slice(), Some(&name))?;
+            }
 
-            Ok(())
+            Ok(save_path)
         })
         .await
     }
@@ -0,0 +1,330 @@
+//! Translations of the text that is shown in the UI.
+//!
+//! Messages are stored in Fluent style `.ftl` files in the `locales` folder,
+//! one file per language. Only the parts of the Fluent syntax that we need are
+//! supported: `id = value` messages whose value can continue on indented
+//! lines, `#` comments, `{ $name }` placeables that are replaced with
+//! arguments and `{ $name -> ... }` select expressions that choose a variant
+//! from an argument, for example a plural form from a count:
+//!
+//! ```ftl
+//! more-tabs = and { $count ->
+//!         [one] one more tab
+//!        *[other] { $count } more tabs
+//!     }
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+    sync::{
+        OnceLock,
+        atomic::{AtomicU8, Ordering},
+    },
+};
+
+/// The language that [`message`] uses.
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::English as u8);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[repr(u8)]
+pub enum Language {
+    #[default]
+    English,
+    Swedish,
+}
+impl Language {
+    pub fn all() -> &'static [Self] {
+        &[Self::English, Self::Swedish]
+    }
+    /// Name of the language in the language itself.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Swedish => "Svenska",
+        }
+    }
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en.ftl"),
+            Language::Swedish => include_str!("../locales/sv.ftl"),
+        }
+    }
+    fn messages(self) -> &'static HashMap<&'static str, String> {
+        static ENGLISH: OnceLock<HashMap<&'static str, String>> = OnceLock::new();
+        static SWEDISH: OnceLock<HashMap<&'static str, String>> = OnceLock::new();
+        match self {
+            Language::English => &ENGLISH,
+            Language::Swedish => &SWEDISH,
+        }
+        .get_or_init(|| parse(self.source()))
+    }
+}
+
+/// Change the language of all messages, including messages created by
+/// background tasks.
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    let current = LANGUAGE.load(Ordering::Relaxed);
+    Language::all()
+        .iter()
+        .copied()
+        .find(|language| *language as u8 == current)
+        .unwrap_or_default()
+}
+
+/// Look up a message in the current language and fill in its arguments.
+/// Falls back to English if the message isn't translated and to the message
+/// id if it doesn't exist at all. Usually called through [`tr!`].
+pub fn message(id: &str, args: &[(&str, &dyn Display)]) -> String {
+    let Some(pattern) = language()
+        .messages()
+        .get(id)
+        .or_else(|| Language::English.messages().get(id))
+    else {
+        log::warn!("Missing translation for \"{id}\"");
+        return id.to_owned();
+    };
+    format_pattern(pattern, args)
+}
+
+/// Get a translated message, arguments are given as `name = value` pairs:
+///
+/// ```ignore
+/// tr!("status-saved", path = path.display())
+/// ```
+macro_rules! tr {
+    ($id:literal) => {
+        $crate::i18n::message($id, &[])
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::message(
+            $id,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
+pub(crate) use tr;
+
+fn parse(source: &'static str) -> HashMap<&'static str, String> {
+    let mut messages = HashMap::new();
+    let mut current: Option<(&'static str, String)> = None;
+    for line in source.lines() {
+        if line.starts_with(char::is_whitespace) && !line.trim().is_empty() {
+            // Continuation of the previous message:
+            if let Some((_, value)) = &mut current {
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        messages.extend(current.take());
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once('=') {
+            Some((id, value)) => current = Some((id.trim(), value.trim().to_owned())),
+            None => log::warn!("Invalid line in translations: {line}"),
+        }
+    }
+    messages.extend(current);
+    messages
+}
+
+/// Replace `{ $name }` placeables with arguments, `{ "text" }` with the
+/// quoted text and `{ $name -> ... }` select expressions with the variant
+/// that matches the argument.
+fn format_pattern(pattern: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut output = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = closing_brace(&rest[start..]).map(|end| start + end) else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        let placeable = rest[start + 1..end].trim();
+        if let Some((selector, variants)) = placeable.split_once("->") {
+            match select(selector.trim(), variants, args) {
+                Some(variant) => output.push_str(&format_pattern(variant, args)),
+                None => output.push_str(&rest[start..=end]),
+            }
+        } else if let Some(name) = placeable.strip_prefix('$') {
+            match args.iter().find(|(arg, _)| *arg == name) {
+                Some((_, value)) => {
+                    let _ = write!(output, "{value}");
+                }
+                None => output.push_str(&rest[start..=end]),
+            }
+        } else if let Some(text) = placeable
+            .strip_prefix('"')
+            .and_then(|text| text.strip_suffix('"'))
+        {
+            output.push_str(text);
+        } else {
+            output.push_str(&rest[start..=end]);
+        }
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Index of the `}` that closes the `{` at the start of `text`.
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0_usize;
+    for (index, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The variant of a select expression that matches the selector. Each
+/// variant is on its own line and starts with `[key]`, the default variant
+/// with `*[key]`. Numbers match a variant with the same number or with their
+/// plural category.
+fn select<'a>(selector: &str, variants: &'a str, args: &[(&str, &dyn Display)]) -> Option<&'a str> {
+    let name = selector.strip_prefix('$')?;
+    let value = args
+        .iter()
+        .find(|(arg, _)| *arg == name)
+        .map(|(_, value)| value.to_string());
+    let category = value.as_deref().and_then(plural_category);
+
+    let mut default = None;
+    let mut by_category = None;
+    for variant in variant_lines(variants) {
+        let (is_default, variant) = match variant.strip_prefix('*') {
+            Some(variant) => (true, variant),
+            None => (false, variant),
+        };
+        let Some((key, text)) = variant
+            .strip_prefix('[')
+            .and_then(|variant| variant.split_once(']'))
+        else {
+            log::warn!("Invalid variant in translations: {variant}");
+            continue;
+        };
+        let (key, text) = (key.trim(), text.trim());
+        if value.as_deref() == Some(key) {
+            return Some(text);
+        }
+        if category == Some(key) {
+            by_category.get_or_insert(text);
+        }
+        if is_default {
+            default = Some(text);
+        }
+    }
+    by_category.or(default)
+}
+
+/// Split the variants of a select expression into lines, ignoring line
+/// breaks inside placeables.
+fn variant_lines(variants: &str) -> impl Iterator<Item = &str> {
+    let mut depth = 0_usize;
+    variants
+        .split(move |c| {
+            match c {
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            c == '\n' && depth == 0
+        })
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+}
+
+/// The plural category of a number. English and Swedish only have two:
+/// `one` for exactly 1 and `other` for everything else.
+fn plural_category(value: &str) -> Option<&'static str> {
+    value.parse::<f64>().ok()?;
+    Some(if value == "1" { "one" } else { "other" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "\
+# Comment
+simple = Hello { $name }!
+multi = First line
+    second line
+plural = { $count ->
+        [0] No tabs
+        [one] One tab
+       *[other] { $count } tabs
+    } left
+nested = { $kind ->
+        [url] { $count ->
+            [one] one URL
+           *[other] { $count } URLs
+        }
+       *[other] something
+    }
+";
+
+    fn format(id: &str, args: &[(&str, &dyn Display)]) -> String {
+        format_pattern(&parse(SOURCE)[id], args)
+    }
+
+    #[test]
+    fn parses_messages() {
+        let messages = parse(SOURCE);
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages["multi"], "First line\nsecond line");
+        assert_eq!(format("simple", &[("name", &"Firefox")]), "Hello Firefox!");
+        // Missing arguments are left as they are:
+        assert_eq!(format("simple", &[]), "Hello { $name }!");
+    }
+
+    #[test]
+    fn selects_plural_forms() {
+        assert_eq!(format("plural", &[("count", &0)]), "No tabs left");
+        assert_eq!(format("plural", &[("count", &1)]), "One tab left");
+        assert_eq!(format("plural", &[("count", &2)]), "2 tabs left");
+        assert_eq!(format("plural", &[("count", &1.5)]), "1.5 tabs left");
+        // The default variant is used when the argument is missing:
+        assert_eq!(format("plural", &[]), "{ $count } tabs left");
+    }
+
+    #[test]
+    fn selects_nested_variants() {
+        let args = |kind: &'static str, count: usize| {
+            format("nested", &[("kind", &kind), ("count", &count)])
+        };
+        assert_eq!(args("url", 1), "one URL");
+        assert_eq!(args("url", 3), "3 URLs");
+        assert_eq!(args("title", 3), "something");
+    }
+
+    #[test]
+    fn translations_have_the_same_messages() {
+        let english = parse(Language::English.source());
+        for &language in Language::all() {
+            let messages = parse(language.source());
+            let mut missing = english
+                .keys()
+                .filter(|id| !messages.contains_key(*id))
+                .collect::<Vec<_>>();
+            missing.sort();
+            assert!(missing.is_empty(), "{language:?} is missing {missing:?}");
+        }
+    }
+}
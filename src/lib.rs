@@ -3,6 +3,19 @@
 mod app;
 mod background;
 pub mod clipboard;
+mod command_palette;
+mod containers;
 mod egui_utils;
+mod exclude_rules;
 mod host;
+mod i18n;
+mod presets;
+mod preview;
+mod save_path;
+mod session;
+mod shortcuts;
+mod statistics;
+mod status_log;
+mod undo;
+mod url_cleaning;
 pub use app::FirefoxSessionDataApp;
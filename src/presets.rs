@@ -0,0 +1,167 @@
+//! Named selections of windows and export options that can be saved and
+//! applied again later, for example when exporting the same windows every
+//! week.
+
+use crate::{
+    host::{
+        FormatInfo, GenerateOptions, OutputOptions, TabGrouping, TabHistory, TabSort, WindowSort,
+    },
+    url_cleaning::UrlCleaning,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct SelectionPreset {
+    pub name: String,
+    /// Apply this preset automatically when a session is loaded.
+    pub apply_on_load: bool,
+
+    pub grouping: TabGrouping,
+    pub open_group_indexes: Option<Vec<u32>>,
+    pub closed_group_indexes: Option<Vec<u32>>,
+    pub table_of_content: bool,
+    pub tab_tree: bool,
+    pub container_labels: bool,
+    pub history: TabHistory,
+    pub url_cleaning: UrlCleaning,
+    pub window_sort: WindowSort,
+    pub tab_sort: TabSort,
+
+    /// Name of the output format, see [`FormatInfo::as_str`].
+    pub format: String,
+    pub tab_search: String,
+    pub tab_container_filter: Option<u32>,
+}
+impl Default for SelectionPreset {
+    fn default() -> Self {
+        Self::new(
+            String::new(),
+            &GenerateOptions::default(),
+            OutputOptions::default().format,
+        )
+    }
+}
+
+impl SelectionPreset {
+    /// Create a preset from the current options.
+    pub fn new(name: String, options: &GenerateOptions, format: FormatInfo) -> Self {
+        Self {
+            name,
+            apply_on_load: false,
+            grouping: options.grouping,
+            open_group_indexes: options.open_group_indexes.clone(),
+            closed_group_indexes: options.closed_group_indexes.clone(),
+            table_of_content: options.table_of_content,
+            tab_tree: options.tab_tree,
+            container_labels: options.container_labels,
+            history: options.history,
+            url_cleaning: options.url_cleaning,
+            window_sort: options.window_sort,
+            tab_sort: options.tab_sort,
+            format: format.as_str().to_owned(),
+            tab_search: String::new(),
+            tab_container_filter: None,
+        }
+    }
+
+    /// Change `options` to match this preset. Only groups that exist in the
+    /// loaded session are selected.
+    ///
+    /// Returns `false` if the preset selected groups but none of them exist,
+    /// then nothing is selected.
+    pub fn apply_to(
+        &self,
+        options: &mut GenerateOptions,
+        open_groups: usize,
+        closed_groups: usize,
+    ) -> bool {
+        let existing = |indexes: &Option<Vec<u32>>, count: usize| {
+            indexes.as_ref().map(|indexes| {
+                indexes
+                    .iter()
+                    .copied()
+                    .filter(|&index| (index as usize) < count)
+                    .collect::<Vec<_>>()
+            })
+        };
+        options.grouping = self.grouping;
+        options.open_group_indexes = existing(&self.open_group_indexes, open_groups);
+        options.closed_group_indexes = existing(&self.closed_group_indexes, closed_groups);
+        let stored = self.open_group_indexes.as_ref().map_or(0, Vec::len)
+            + self.closed_group_indexes.as_ref().map_or(0, Vec::len);
+        let all_missing = stored > 0
+            && options.selected_groups() == 0
+            && options.open_group_indexes.is_some()
+            && options.closed_group_indexes.is_some();
+        options.excluded_native_groups.clear();
+        options.table_of_content = self.table_of_content;
+        options.tab_tree = self.tab_tree;
+        options.container_labels = self.container_labels;
+        options.history = self.history;
+        options.url_cleaning = self.url_cleaning;
+        options.window_sort = self.window_sort;
+        options.tab_sort = self.tab_sort;
+        !all_missing
+    }
+
+    /// The output format that this preset uses, `None` if it is no longer
+    /// supported.
+    pub fn format(&self) -> Option<FormatInfo> {
+        FormatInfo::all()
+            .iter()
+            .copied()
+            .find(|format| format.as_str() == self.format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied(open: Option<Vec<u32>>, closed: Option<Vec<u32>>) -> (GenerateOptions, bool) {
+        let preset = SelectionPreset {
+            open_group_indexes: open,
+            closed_group_indexes: closed,
+            ..Default::default()
+        };
+        let mut options = GenerateOptions::default();
+        let found = preset.apply_to(&mut options, 2, 1);
+        (options, found)
+    }
+
+    #[test]
+    fn selection_is_applied_exactly() {
+        let cases = [
+            (None, None),
+            (None, Some(vec![])),
+            (Some(vec![]), None),
+            (Some(vec![]), Some(vec![])),
+            (Some(vec![1]), Some(vec![])),
+            (Some(vec![]), Some(vec![0])),
+        ];
+        for (open, closed) in cases {
+            let (options, found) = applied(open.clone(), closed.clone());
+            assert!(found);
+            assert_eq!(options.open_group_indexes, open);
+            assert_eq!(options.closed_group_indexes, closed);
+        }
+    }
+
+    #[test]
+    fn missing_groups_are_skipped() {
+        let (options, found) = applied(Some(vec![0, 5]), Some(vec![3]));
+        assert!(found);
+        assert_eq!(options.open_group_indexes, Some(vec![0]));
+        assert_eq!(options.closed_group_indexes, Some(vec![]));
+
+        // Nothing is selected instead of falling back to all open windows:
+        let (options, found) = applied(Some(vec![5]), Some(vec![3]));
+        assert!(!found);
+        assert!(options.selects_nothing());
+
+        let (options, found) = applied(None, Some(vec![3]));
+        assert!(found);
+        assert_eq!(options.open_group_indexes, None);
+        assert_eq!(options.closed_group_indexes, Some(vec![]));
+    }
+}
@@ -0,0 +1,690 @@
+//! A simple formatted view of generated links.
+//!
+//! This isn't a full Markdown or HTML renderer, it only understands the
+//! subset that is used when converting tabs to links: headings, links and
+//! (nested) lists.
+
+use egui::Widget;
+
+use crate::clipboard::ClipboardContent;
+
+/// The markup language that a generated preview is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    /// Can't be rendered, so the source is always shown as is.
+    Plain,
+    Markdown,
+    Html,
+}
+impl Syntax {
+    pub fn can_render(self) -> bool {
+        !matches!(self, Self::Plain)
+    }
+    pub fn parse(self, source: &str) -> Vec<Block> {
+        match self {
+            Syntax::Plain => Vec::new(),
+            Syntax::Markdown => parse_markdown(source),
+            Syntax::Html => parse_html(source),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    Heading {
+        level: u8,
+        text: String,
+    },
+    Link {
+        indent: usize,
+        title: String,
+        url: String,
+    },
+    Text {
+        indent: usize,
+        text: String,
+    },
+}
+
+/// Links generated in a specific format, optionally parsed so that they can
+/// be rendered.
+#[derive(Debug, Clone)]
+pub struct Preview {
+    pub source: String,
+    pub syntax: Syntax,
+    pub blocks: Vec<Block>,
+}
+impl Preview {
+    pub fn new(source: String, syntax: Syntax) -> Self {
+        Self {
+            blocks: syntax.parse(&source),
+            syntax,
+            source,
+        }
+    }
+}
+impl Preview {
+    /// Content to put on the clipboard when copying these links. Formats that
+    /// can be rendered are also copied as HTML so that links keep working when
+    /// pasted into documents or emails.
+    pub fn clipboard_content(&self) -> ClipboardContent {
+        match self.syntax {
+            Syntax::Plain => ClipboardContent {
+                text: self.source.clone(),
+                html: None,
+            },
+            Syntax::Markdown => ClipboardContent {
+                text: self.source.clone(),
+                html: Some(blocks_to_html(&self.blocks)),
+            },
+            Syntax::Html => ClipboardContent {
+                text: blocks_to_plain_text(&self.blocks),
+                html: Some(self.source.clone()),
+            },
+        }
+    }
+}
+impl Default for Preview {
+    fn default() -> Self {
+        Self::new(String::new(), Syntax::Plain)
+    }
+}
+
+fn unescape_markdown(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\'
+            && let Some(escaped) = chars.next()
+        {
+            result.push(escaped);
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Find a `[title](url)` style link in a Markdown line.
+fn find_markdown_link(line: &str) -> Option<(String, String)> {
+    if let Some(url) = line.strip_prefix('<').and_then(|v| v.strip_suffix('>')) {
+        return Some((url.to_owned(), url.to_owned()));
+    }
+    let start = line.find('[')?;
+    let middle = start + line[start..].rfind("](")?;
+    let end = middle + line[middle..].rfind(')')?;
+    let title = &line[start + 1..middle];
+    let url = line[middle + 2..end].trim_start_matches('<');
+    let url = url.trim_end_matches('>');
+    Some((unescape_markdown(title), url.to_owned()))
+}
+
+pub fn parse_markdown(source: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    for line in source.lines() {
+        let width = line
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum::<usize>();
+        let line = line.trim();
+        if line.is_empty() || line.chars().all(|c| matches!(c, '-' | '*' | '_')) {
+            continue;
+        }
+
+        let hashes = line.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&hashes) && line[hashes..].starts_with(' ') {
+            blocks.push(Block::Heading {
+                level: hashes as u8,
+                text: unescape_markdown(line[hashes..].trim()),
+            });
+            continue;
+        }
+
+        let mut indent = width / 2;
+        let mut content = line;
+        if let Some(rest) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| line.strip_prefix(marker))
+        {
+            content = rest;
+            indent += 1;
+        } else if let Some((number, rest)) = line.split_once(". ")
+            && !number.is_empty()
+            && number.chars().all(|c| c.is_ascii_digit())
+        {
+            content = rest;
+            indent += 1;
+        }
+
+        blocks.push(if let Some((title, url)) = find_markdown_link(content) {
+            Block::Link { indent, title, url }
+        } else {
+            Block::Text {
+                indent,
+                text: unescape_markdown(content),
+            }
+        });
+    }
+    blocks
+}
+
+fn decode_html_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_owned();
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => ' ',
+                entity => {
+                    let code = entity.strip_prefix('#')?;
+                    let code = if let Some(hex) = code.strip_prefix(['x', 'X']) {
+                        u32::from_str_radix(hex, 16).ok()?
+                    } else {
+                        code.parse().ok()?
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end))
+        });
+        if let Some((c, end)) = decoded {
+            result.push(c);
+            rest = &rest[end + 1..];
+        } else {
+            result.push('&');
+            rest = &rest[1..];
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Get the value of an attribute from the inside of a HTML start tag.
+fn html_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    // Skip the tag name:
+    let mut rest = tag.trim_start_matches(|c: char| !c.is_whitespace() && c != '/');
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if rest.is_empty() {
+            return None;
+        }
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let attribute = &rest[..name_end];
+        rest = rest[name_end..].trim_start();
+
+        let value = if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let after = &after[1..];
+                    let end = after.find(quote)?;
+                    rest = &after[end + 1..];
+                    &after[..end]
+                }
+                _ => {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    rest = &after[end..];
+                    &after[..end]
+                }
+            }
+        } else {
+            ""
+        };
+        if attribute.eq_ignore_ascii_case(name) {
+            return Some(value);
+        }
+    }
+}
+
+/// Take the text that has been collected so far and normalize its whitespace.
+fn take_html_text(text: &mut String) -> String {
+    let collected = decode_html_entities(&text.split_whitespace().collect::<Vec<_>>().join(" "));
+    text.clear();
+    collected
+}
+
+fn flush_html_text(text: &mut String, indent: usize, blocks: &mut Vec<Block>) {
+    let text = take_html_text(text);
+    if !text.is_empty() {
+        blocks.push(Block::Text { indent, text });
+    }
+}
+
+pub fn parse_html(source: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    // Nesting of list elements:
+    let mut indent = 0_usize;
+    // Tag that we are currently collecting text for:
+    let mut heading: Option<u8> = None;
+    let mut link: Option<String> = None;
+    let mut skip_until: Option<&str> = None;
+    let mut text = String::new();
+
+    let mut rest = source;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            text.push_str(rest);
+            break;
+        };
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>').map(|end| start + end) else {
+            break;
+        };
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        let is_end_tag = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if let Some(skipped) = skip_until {
+            if is_end_tag && name == skipped {
+                skip_until = None;
+                text.clear();
+            }
+            continue;
+        }
+
+        match (name.as_str(), is_end_tag) {
+            ("head" | "style" | "script" | "title", false) => {
+                flush_html_text(&mut text, indent, &mut blocks);
+                skip_until = Some(match name.as_str() {
+                    "head" => "head",
+                    "style" => "style",
+                    "script" => "script",
+                    _ => "title",
+                });
+            }
+            ("ul" | "ol", false) => {
+                flush_html_text(&mut text, indent, &mut blocks);
+                indent += 1;
+            }
+            ("ul" | "ol", true) => {
+                flush_html_text(&mut text, indent, &mut blocks);
+                indent = indent.saturating_sub(1);
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                flush_html_text(&mut text, indent, &mut blocks);
+                heading = name[1..].parse().ok();
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => {
+                if let Some(level) = heading.take() {
+                    blocks.push(Block::Heading {
+                        level,
+                        text: take_html_text(&mut text),
+                    });
+                }
+            }
+            ("a", false) if heading.is_none() => {
+                flush_html_text(&mut text, indent, &mut blocks);
+                link = Some(decode_html_entities(
+                    html_attribute(tag, "href").unwrap_or_default(),
+                ));
+            }
+            ("a", true) => {
+                if let Some(url) = link.take() {
+                    let title = take_html_text(&mut text);
+                    blocks.push(Block::Link {
+                        indent,
+                        title: if title.is_empty() { url.clone() } else { title },
+                        url,
+                    });
+                }
+            }
+            ("br" | "p" | "li" | "div" | "tr", _) if heading.is_none() && link.is_none() => {
+                flush_html_text(&mut text, indent, &mut blocks);
+            }
+            _ => {}
+        }
+    }
+    if skip_until.is_some() {
+        // Unclosed `<script>` or `<style>` element:
+        text.clear();
+    }
+    flush_html_text(&mut text, indent, &mut blocks);
+    blocks
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Simple HTML document with the same content as the parsed blocks.
+pub fn blocks_to_html(blocks: &[Block]) -> String {
+    let mut html = String::new();
+    let mut depth = 0;
+    for block in blocks {
+        let indent = match block {
+            Block::Heading { .. } => 0,
+            Block::Link { indent, .. } | Block::Text { indent, .. } => *indent,
+        };
+        while depth < indent {
+            html.push_str("<ul>\n");
+            depth += 1;
+        }
+        while depth > indent {
+            html.push_str("</ul>\n");
+            depth -= 1;
+        }
+        let item = |content: String| {
+            if depth > 0 {
+                format!("<li>{content}</li>\n")
+            } else {
+                format!("<p>{content}</p>\n")
+            }
+        };
+        html.push_str(&match block {
+            Block::Heading { level, text } => {
+                let level = level.clamp(&1, &6);
+                format!("<h{level}>{}</h{level}>\n", escape_html(text))
+            }
+            Block::Link { title, url, .. } => item(format!(
+                "<a href=\"{}\">{}</a>",
+                escape_html(url),
+                escape_html(title)
+            )),
+            Block::Text { text, .. } => item(escape_html(text)),
+        });
+    }
+    for _ in 0..depth {
+        html.push_str("</ul>\n");
+    }
+    html
+}
+
+/// Plain text version of the parsed blocks with one link per line.
+pub fn blocks_to_plain_text(blocks: &[Block]) -> String {
+    let mut text = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading { text: heading, .. } => {
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(heading);
+                text.push('\n');
+            }
+            Block::Link { indent, title, url } => {
+                text.push_str(&"  ".repeat(*indent));
+                if title != url {
+                    text.push_str(title);
+                    text.push_str(" - ");
+                }
+                text.push_str(url);
+                text.push('\n');
+            }
+            Block::Text {
+                indent,
+                text: content,
+            } => {
+                text.push_str(&"  ".repeat(*indent));
+                text.push_str(content);
+                text.push('\n');
+            }
+        }
+    }
+    text
+}
+
+/// Content to put on the clipboard when copying a single link.
+pub fn link_clipboard_content(title: &str, url: &str, syntax: Syntax) -> ClipboardContent {
+    let link = [Block::Link {
+        indent: 0,
+        title: title.to_owned(),
+        url: url.to_owned(),
+    }];
+    match syntax {
+        Syntax::Plain => url.to_owned().into(),
+        Syntax::Markdown => ClipboardContent {
+            text: crate::session::markdown_link(title, url),
+            html: Some(blocks_to_html(&link)),
+        },
+        Syntax::Html => ClipboardContent {
+            text: url.to_owned(),
+            html: Some(blocks_to_html(&link)),
+        },
+    }
+}
+
+/// Show parsed blocks. Links can be clicked to open them.
+pub fn show_blocks(ui: &mut egui::Ui, blocks: &[Block]) {
+    for block in blocks {
+        match block {
+            Block::Heading { level, text } => {
+                ui.add_space(4.0);
+                let text = egui::RichText::new(text).strong();
+                if *level <= 1 {
+                    ui.label(text.heading());
+                } else {
+                    ui.label(text);
+                }
+            }
+            Block::Link { indent, title, url } => {
+                ui.horizontal(|ui| {
+                    ui.add_space(*indent as f32 * 16.0);
+                    egui::Hyperlink::from_label_and_url(title, url)
+                        .open_in_new_tab(true)
+                        .ui(ui)
+                        .on_hover_text(url);
+                });
+            }
+            Block::Text { indent, text } => {
+                ui.horizontal(|ui| {
+                    ui.add_space(*indent as f32 * 16.0);
+                    ui.label(text);
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(indent: usize, title: &str, url: &str) -> Block {
+        Block::Link {
+            indent,
+            title: title.to_owned(),
+            url: url.to_owned(),
+        }
+    }
+
+    fn text(indent: usize, text: &str) -> Block {
+        Block::Text {
+            indent,
+            text: text.to_owned(),
+        }
+    }
+
+    fn heading(level: u8, text: &str) -> Block {
+        Block::Heading {
+            level,
+            text: text.to_owned(),
+        }
+    }
+
+    #[test]
+    fn markdown_nested_lists() {
+        let source = "\
+# Window 1
+
+- [Parent](https://example.com/)
+  - [Child](https://example.com/child)
+    - [Grandchild](https://example.com/grandchild)
+\t- [Tab indented](https://example.com/tab)
+1. [Numbered](https://example.com/1)
+---
+";
+        assert_eq!(
+            parse_markdown(source),
+            vec![
+                heading(1, "Window 1"),
+                link(1, "Parent", "https://example.com/"),
+                link(2, "Child", "https://example.com/child"),
+                link(3, "Grandchild", "https://example.com/grandchild"),
+                link(3, "Tab indented", "https://example.com/tab"),
+                link(1, "Numbered", "https://example.com/1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn markdown_escapes_and_special_urls() {
+        assert_eq!(
+            parse_markdown(
+                "## \\#1 \\*tabs\\*\n\
+                 - [\\[draft\\] notes](<https://example.com/a b>)\n\
+                 - [Wiki](https://en.wikipedia.org/wiki/Rust_(programming_language))\n\
+                 <https://example.com/auto>"
+            ),
+            vec![
+                heading(2, "#1 *tabs*"),
+                link(1, "[draft] notes", "https://example.com/a b"),
+                link(
+                    1,
+                    "Wiki",
+                    "https://en.wikipedia.org/wiki/Rust_(programming_language)"
+                ),
+                link(0, "https://example.com/auto", "https://example.com/auto"),
+            ]
+        );
+    }
+
+    #[test]
+    fn markdown_malformed_input() {
+        assert_eq!(
+            parse_markdown("#no space\n- [unclosed\n- [a](b\n-  \n1.no space"),
+            vec![
+                text(0, "#no space"),
+                text(1, "[unclosed"),
+                text(1, "[a](b"),
+                text(0, "1.no space"),
+            ]
+        );
+        assert_eq!(parse_markdown(""), vec![]);
+    }
+
+    #[test]
+    fn html_entities() {
+        assert_eq!(decode_html_entities("no entities"), "no entities");
+        assert_eq!(
+            decode_html_entities("&lt;b&gt; &amp; &quot;q&quot; &apos;a&apos;&nbsp;!"),
+            "<b> & \"q\" 'a' !"
+        );
+        assert_eq!(decode_html_entities("&#65;&#x42;&#X43;&#x1F600;"), "ABC😀");
+        // Decoding happens once, so escaped entities stay escaped:
+        assert_eq!(decode_html_entities("&amp;lt;"), "&lt;");
+    }
+
+    #[test]
+    fn html_malformed_entities() {
+        assert_eq!(decode_html_entities("a & b"), "a & b");
+        assert_eq!(decode_html_entities("a & b; c"), "a & b; c");
+        assert_eq!(decode_html_entities("&unknown;"), "&unknown;");
+        assert_eq!(
+            decode_html_entities("&#;&#xZZ;&#xD800;"),
+            "&#;&#xZZ;&#xD800;"
+        );
+        assert_eq!(decode_html_entities("trailing &"), "trailing &");
+        assert_eq!(decode_html_entities("&&amp;"), "&&");
+    }
+
+    #[test]
+    fn html_attribute_quoting() {
+        assert_eq!(html_attribute(r#"a href="x y""#, "href"), Some("x y"));
+        assert_eq!(html_attribute("a href='x \"y\"'", "href"), Some("x \"y\""));
+        assert_eq!(html_attribute("a href=x title=y", "href"), Some("x"));
+        assert_eq!(html_attribute("a href = \"x\"", "href"), Some("x"));
+        assert_eq!(html_attribute("a HREF=\"x\"", "href"), Some("x"));
+        assert_eq!(html_attribute("a download href=x", "href"), Some("x"));
+        assert_eq!(html_attribute("a href=x/", "href"), Some("x/"));
+    }
+
+    #[test]
+    fn html_attribute_needs_exact_name() {
+        assert_eq!(
+            html_attribute(r#"a data-href="x" href="y""#, "href"),
+            Some("y")
+        );
+        assert_eq!(
+            html_attribute(r#"a title="see href=x" href="y""#, "href"),
+            Some("y")
+        );
+        assert_eq!(html_attribute("href", "href"), None);
+        assert_eq!(html_attribute("a title=x", "href"), None);
+        assert_eq!(html_attribute("a href=\"unclosed", "href"), None);
+    }
+
+    #[test]
+    fn html_nested_lists() {
+        let source = r#"<!DOCTYPE html>
+<html><head><title>Ignored</title><style>a { color: red; }</style></head>
+<body>
+<h1>Window &amp; tabs</h1>
+<ul>
+  <li><a href="https://example.com/?a=1&amp;b=2">Parent</a>
+    <ul>
+      <li><a href='https://example.com/child'>Child   with
+        space</a></li>
+      <li>Plain &lt;text&gt;</li>
+    </ul>
+  </li>
+  <li><a href="https://example.com/empty"></a></li>
+</ul>
+</body></html>"#;
+        assert_eq!(
+            parse_html(source),
+            vec![
+                heading(1, "Window & tabs"),
+                link(1, "Parent", "https://example.com/?a=1&b=2"),
+                link(2, "Child with space", "https://example.com/child"),
+                text(2, "Plain <text>"),
+                link(1, "https://example.com/empty", "https://example.com/empty"),
+            ]
+        );
+    }
+
+    #[test]
+    fn html_malformed_input() {
+        // Unclosed lists and a tag that never ends:
+        assert_eq!(
+            parse_html("<ul><li>first<ul><li>second</ul></ul></ul><ol><li>third <b"),
+            vec![text(1, "first"), text(2, "second"), text(1, "third")]
+        );
+        // Stray end tags and text outside of any element:
+        assert_eq!(
+            parse_html("</a></h2>loose text<br>after"),
+            vec![text(0, "loose text"), text(0, "after")]
+        );
+        assert_eq!(parse_html(""), vec![]);
+        assert_eq!(parse_html("<script>never closed"), vec![]);
+    }
+}
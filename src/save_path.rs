@@ -0,0 +1,356 @@
+//! Helpers for resolving the path that links are exported to.
+//!
+//! The save path that the user enters can contain placeholders like
+//! `{profile}-{date}-{windows}.{ext}` that are expanded right before a file is
+//! written, that way the same path can be reused for every export.
+
+use std::path::{Path, PathBuf};
+
+/// Placeholders that are supported in a save path template, together with the
+/// message id of a short description that can be shown as a tooltip.
+pub const PLACEHOLDERS: &[(&str, &str)] = &[
+    ("{profile}", "placeholder-profile"),
+    ("{date}", "placeholder-date"),
+    ("{time}", "placeholder-time"),
+    ("{windows}", "placeholder-windows"),
+    ("{format}", "placeholder-format"),
+    ("{ext}", "placeholder-ext"),
+];
+
+/// File name used by default when exporting links.
+pub const DEFAULT_FILE_NAME: &str = "firefox-links-{date}";
+
+/// Values that are substituted into a save path template.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateValues {
+    pub profile: String,
+    pub windows: usize,
+    pub format: String,
+    pub ext: String,
+}
+
+/// Find the user's downloads folder.
+///
+/// On Linux this respects `XDG_DOWNLOAD_DIR` (both as an environment variable
+/// and from `user-dirs.dirs`) and otherwise falls back to `~/Downloads`.
+#[cfg(not(target_family = "wasm"))]
+pub fn downloads_dir() -> Option<PathBuf> {
+    #[cfg(windows)]
+    {
+        std::env::var_os("USERPROFILE").map(|home| PathBuf::from(home).join("Downloads"))
+    }
+    #[cfg(not(windows))]
+    {
+        let home = PathBuf::from(std::env::var_os("HOME")?);
+
+        #[cfg(not(target_os = "macos"))]
+        if let Some(dir) = xdg_download_dir(&home) {
+            return Some(dir);
+        }
+
+        Some(home.join("Downloads"))
+    }
+}
+#[cfg(target_family = "wasm")]
+pub fn downloads_dir() -> Option<PathBuf> {
+    None
+}
+
+/// Read the downloads folder from the XDG user directories config, see
+/// <https://www.freedesktop.org/wiki/Software/xdg-user-dirs/>.
+#[cfg(all(not(target_family = "wasm"), not(windows), not(target_os = "macos")))]
+fn xdg_download_dir(home: &Path) -> Option<PathBuf> {
+    let expand = |value: &str| -> Option<PathBuf> {
+        let value = value.trim().trim_matches('"');
+        let path = if let Some(rest) = value.strip_prefix("$HOME") {
+            home.join(rest.trim_start_matches('/'))
+        } else {
+            PathBuf::from(value)
+        };
+        // XDG considers a value equal to the home folder as "disabled":
+        (path.is_absolute() && path != home).then_some(path)
+    };
+
+    if let Ok(dir) = std::env::var("XDG_DOWNLOAD_DIR")
+        && let Some(dir) = expand(&dir)
+    {
+        return Some(dir);
+    }
+
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .unwrap_or_else(|| home.join(".config"));
+    let config = std::fs::read_to_string(config_home.join("user-dirs.dirs")).ok()?;
+    config
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .find_map(|line| line.trim().strip_prefix("XDG_DOWNLOAD_DIR="))
+        .and_then(expand)
+}
+
+/// The save path that is used if the user hasn't specified one. Without a
+/// downloads folder (for example on the web) it is only a file name.
+pub fn default_save_path() -> String {
+    downloads_dir()
+        .map(|dir| dir.join(DEFAULT_FILE_NAME).to_string_lossy().into_owned())
+        .unwrap_or_else(|| DEFAULT_FILE_NAME.to_owned())
+}
+
+/// Guess the browser profile folder that a session file was loaded from.
+///
+/// Handles Firefox's `sessionstore-backups` folder and Chromium's `Sessions`
+/// folder by skipping over them to get to the actual profile folder.
+pub fn profile_dir_from_session_path(path: &Path) -> Option<&Path> {
+    let mut folder = if path.is_dir() || path.extension().is_none() {
+        path
+    } else {
+        path.parent()?
+    };
+    while let Some(name) = folder.file_name() {
+        if name.eq_ignore_ascii_case("sessionstore-backups")
+            || name.eq_ignore_ascii_case("Sessions")
+        {
+            folder = folder.parent()?;
+        } else {
+            return Some(folder);
+        }
+    }
+    None
+}
+
+/// Guess the name of the browser profile that a session file was loaded from.
+pub fn profile_name_from_session_path(path: &Path) -> Option<String> {
+    let folder = profile_dir_from_session_path(path)?;
+    Some(folder.file_name()?.to_string_lossy().into_owned())
+}
+
+/// Replace characters that aren't allowed in file names on common platforms.
+fn sanitize_file_name_part(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Expand all known placeholders in a save path template. Unknown
+/// placeholders are left as is.
+pub fn expand_template(template: &str, values: &TemplateValues) -> String {
+    let now = chrono::Local::now();
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let replacement = match &rest[..=end] {
+            "{profile}" => sanitize_file_name_part(&values.profile),
+            "{date}" => now.format("%Y-%m-%d").to_string(),
+            "{time}" => now.format("%H-%M-%S").to_string(),
+            "{windows}" => values.windows.to_string(),
+            "{format}" => sanitize_file_name_part(&values.format),
+            "{ext}" => sanitize_file_name_part(&values.ext),
+            unknown => unknown.to_owned(),
+        };
+        result.push_str(&replacement);
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Give the file name the extension of the output format. The extension of
+/// another output format (one of `known`) is replaced, otherwise `ext` is
+/// appended unless the name already ends with it.
+///
+/// [`Path::extension`] can't be used to decide this since names like a
+/// Firefox profile folder (`xxxxxxxx.default-release`) look like they have an
+/// extension.
+pub fn with_extension(path: PathBuf, ext: &str, known: &[&str]) -> PathBuf {
+    let Some(name) = path.file_name() else {
+        return path;
+    };
+    if ext.is_empty() {
+        return path;
+    }
+    let name = name.to_string_lossy();
+    let name = match name.rsplit_once('.') {
+        Some((stem, end)) if !stem.is_empty() && end.eq_ignore_ascii_case(ext) => return path,
+        Some((stem, end))
+            if !stem.is_empty() && known.iter().any(|known| end.eq_ignore_ascii_case(known)) =>
+        {
+            format!("{stem}.{ext}")
+        }
+        _ => format!("{name}.{ext}"),
+    };
+    path.with_file_name(name)
+}
+
+/// Find a path that doesn't exist yet by appending ` (1)`, ` (2)` and so on
+/// to the file name.
+#[cfg(not(target_family = "wasm"))]
+pub fn next_free_path(path: &Path) -> std::io::Result<PathBuf> {
+    if !path.try_exists()? {
+        return Ok(path.to_path_buf());
+    }
+    let stem = path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    let ext = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    for counter in 1_u32.. {
+        let candidate = path.with_file_name(format!("{stem} ({counter}){ext}"));
+        if !candidate.try_exists()? {
+            return Ok(candidate);
+        }
+    }
+    Err(std::io::Error::new(
+        std::io::ErrorKind::AlreadyExists,
+        "no free file name was found",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> TemplateValues {
+        TemplateValues {
+            profile: "abcd1234.default-release".to_owned(),
+            windows: 3,
+            format: "HTML".to_owned(),
+            ext: "html".to_owned(),
+        }
+    }
+
+    #[test]
+    fn expand_known_placeholders() {
+        assert_eq!(
+            expand_template("{profile}-{windows}-{format}.{ext}", &values()),
+            "abcd1234.default-release-3-HTML.html"
+        );
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(
+            expand_template("links-{date}", &values()),
+            format!("links-{today}")
+        );
+    }
+
+    #[test]
+    fn expand_keeps_unknown_and_unclosed_placeholders() {
+        assert_eq!(
+            expand_template("{unknown}-{windows}-{", &values()),
+            "{unknown}-3-{"
+        );
+        assert_eq!(expand_template("a{b", &values()), "a{b");
+        assert_eq!(
+            expand_template("no placeholders", &values()),
+            "no placeholders"
+        );
+    }
+
+    #[test]
+    fn expand_sanitizes_values() {
+        let values = TemplateValues {
+            profile: "a/b\\c:d".to_owned(),
+            ..values()
+        };
+        assert_eq!(expand_template("/tmp/{profile}", &values), "/tmp/a_b_c_d");
+    }
+
+    #[test]
+    fn sanitize_replaces_reserved_characters() {
+        assert_eq!(
+            sanitize_file_name_part(r#"a/b\c:d*e?f"g<h>i|j"#),
+            "a_b_c_d_e_f_g_h_i_j"
+        );
+        assert_eq!(sanitize_file_name_part("tab\tnew\nline"), "tab_new_line");
+        assert_eq!(
+            sanitize_file_name_part("Åäö profile.default"),
+            "Åäö profile.default"
+        );
+    }
+
+    const KNOWN: &[&str] = &["pdf", "txt", "html", "md"];
+
+    #[test]
+    fn extension_is_appended_to_profile_like_names() {
+        assert_eq!(
+            with_extension(PathBuf::from("out/abcd1234.default-release"), "pdf", KNOWN),
+            PathBuf::from("out/abcd1234.default-release.pdf")
+        );
+        assert_eq!(
+            with_extension(PathBuf::from("out/links"), "md", KNOWN),
+            PathBuf::from("out/links.md")
+        );
+    }
+
+    #[test]
+    fn extension_is_not_repeated() {
+        assert_eq!(
+            with_extension(PathBuf::from("out/links.pdf"), "pdf", KNOWN),
+            PathBuf::from("out/links.pdf")
+        );
+        assert_eq!(
+            with_extension(PathBuf::from("out/links.PDF"), "pdf", KNOWN),
+            PathBuf::from("out/links.PDF")
+        );
+        assert_eq!(
+            with_extension(PathBuf::from("out/.pdf"), "pdf", KNOWN),
+            PathBuf::from("out/.pdf.pdf")
+        );
+    }
+
+    #[test]
+    fn extension_of_other_format_is_replaced() {
+        assert_eq!(
+            with_extension(PathBuf::from("out/links.txt"), "pdf", KNOWN),
+            PathBuf::from("out/links.pdf")
+        );
+        assert_eq!(
+            with_extension(PathBuf::from("out/links.HTML"), "md", KNOWN),
+            PathBuf::from("out/links.md")
+        );
+        assert_eq!(
+            with_extension(PathBuf::from("out/.txt"), "pdf", KNOWN),
+            PathBuf::from("out/.txt.pdf")
+        );
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[test]
+    fn next_free_path_appends_counter() {
+        let folder = std::env::temp_dir().join(format!(
+            "firefox-session-ui-next-free-path-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&folder).unwrap();
+        let path = folder.join("links.default-release.html");
+
+        assert_eq!(next_free_path(&path).unwrap(), path);
+
+        std::fs::write(&path, "").unwrap();
+        let first = folder.join("links.default-release (1).html");
+        assert_eq!(next_free_path(&path).unwrap(), first);
+
+        std::fs::write(&first, "").unwrap();
+        assert_eq!(
+            next_free_path(&path).unwrap(),
+            folder.join("links.default-release (2).html")
+        );
+
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
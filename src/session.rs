@@ -0,0 +1,1106 @@
+//! Tab level info read directly from Firefox's sessionstore JSON data.
+//!
+//! The groups listed by `firefox_session_data` only expose their names, so we
+//! deserialize the parts of the session that we want to show in the UI
+//! ourselves. The JSON text is kept around so that the session can be
+//! modified (for example to exclude some tabs) before it is converted to
+//! links.
+
+#![cfg_attr(
+    not(feature = "real_data"),
+    allow(dead_code, unused_variables, unused_imports)
+)]
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{containers::Containers, url_cleaning::UrlCleaning};
+
+/// Uniquely identifies a tab in a specific session.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Deserialize, serde::Serialize,
+)]
+pub struct TabId {
+    /// `true` if the tab is in an open window, `false` if it is in a closed
+    /// window.
+    pub open: bool,
+    /// Index of the window that contains the tab.
+    pub window: u32,
+    /// `true` if the tab is in the window's list of recently closed tabs.
+    #[serde(default)]
+    pub recently_closed: bool,
+    /// Index of the tab inside its window (or inside the window's recently
+    /// closed tabs).
+    pub tab: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabInfo {
+    pub id: TabId,
+    pub title: String,
+    pub url: String,
+    /// Milliseconds since the Unix epoch.
+    pub last_accessed: Option<i64>,
+    pub pinned: bool,
+    pub hidden: bool,
+    /// The tab is the one that is shown in its window.
+    pub selected: bool,
+    /// The tab was unloaded to save memory.
+    pub discarded: bool,
+    /// Id of the container (contextual identity) that the tab belongs to, `0`
+    /// if it isn't in a container.
+    pub user_context_id: u32,
+    /// Id of the native tab group that the tab is in.
+    pub group_id: Option<String>,
+    /// All pages in the tab's back/forward history, oldest first.
+    pub history: Vec<HistoryEntry>,
+    /// Index into `history` for the page that is currently shown.
+    pub history_index: usize,
+    /// When a recently closed tab was closed, in milliseconds since the Unix
+    /// epoch.
+    pub closed_at: Option<i64>,
+    /// Where the tab is in the tab trees of Sidebery and Tree Style Tab.
+    pub tree: TabTree,
+}
+
+/// A tab's place in a tab tree. Ids are only unique inside the tab's window.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TreeNode {
+    pub id: String,
+    pub parent: Option<String>,
+}
+
+/// Tree data that tab tree extensions stored for a tab.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TabTree {
+    pub sidebery: Option<TreeNode>,
+    pub tst: Option<TreeNode>,
+    pub tst_legacy: Option<TreeNode>,
+}
+impl TabTree {
+    /// The data from each extension, in the order they are preferred when
+    /// converting a session to links.
+    pub fn sources(&self) -> [Option<&TreeNode>; 3] {
+        [
+            self.sidebery.as_ref(),
+            self.tst.as_ref(),
+            self.tst_legacy.as_ref(),
+        ]
+    }
+
+    #[cfg(feature = "real_data")]
+    fn from_ext_data(ext_data: &BTreeMap<String, serde_json::Value>) -> Self {
+        let text = |key: &str| ext_data.get(key)?.as_str();
+        let json = |key: &str| serde_json::from_str::<serde_json::Value>(text(key)?).ok();
+        // Ids can be numbers or strings:
+        let id = |value: &serde_json::Value| match value {
+            serde_json::Value::String(id) if !id.is_empty() => Some(id.clone()),
+            serde_json::Value::Number(id) if id.as_i64().is_none_or(|id| id >= 0) => {
+                Some(id.to_string())
+            }
+            _ => None,
+        };
+
+        let sidebery =
+            json("extension:{3c078156-979c-498b-8990-85f7987dd929}:data").and_then(|data| {
+                Some(TreeNode {
+                    id: id(data.get("id")?)?,
+                    parent: data.get("parentId").and_then(id),
+                })
+            });
+        let tst =
+            json("extension:treestyletab@piro.sakura.ne.jp:data-persistent-id").and_then(|data| {
+                Some(TreeNode {
+                    id: id(data.get("id")?)?,
+                    // Closest ancestor first:
+                    parent: json("extension:treestyletab@piro.sakura.ne.jp:ancestors")
+                        .and_then(|ancestors| id(ancestors.get(0)?)),
+                })
+            });
+        let tst_legacy = text("treestyletab-id")
+            .filter(|id| !id.is_empty())
+            .map(|tab_id| TreeNode {
+                id: tab_id.to_owned(),
+                parent: text("treestyletab-parent")
+                    .filter(|parent| !parent.is_empty())
+                    .map(str::to_owned),
+            });
+        Self {
+            sidebery,
+            tst,
+            tst_legacy,
+        }
+    }
+}
+
+/// A page in a tab's back/forward history.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub title: String,
+    pub url: String,
+}
+
+/// A tab group created with Firefox's native tab groups feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeGroup {
+    pub id: String,
+    pub name: String,
+    /// One of Firefox's tab group colour names, for example `"blue"`.
+    pub color: String,
+    pub collapsed: bool,
+    pub tabs: Vec<TabId>,
+}
+
+/// Identifies either a native tab group or the tabs that aren't in any native
+/// group inside a specific window.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NativeGroupId {
+    pub open: bool,
+    pub window: u32,
+    /// `None` for tabs that aren't in a group.
+    pub group: Option<String>,
+}
+impl NativeGroupId {
+    pub fn of_tab(tab: &TabInfo) -> Self {
+        Self {
+            open: tab.id.open,
+            window: tab.id.window,
+            group: tab.group_id.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WindowInfo {
+    pub tabs: Vec<TabInfo>,
+    /// Native tab groups in the order they appear in the tab strip.
+    pub groups: Vec<NativeGroup>,
+    /// Tabs that were recently closed in an open window, most recently
+    /// closed first.
+    pub closed_tabs: Vec<TabInfo>,
+    /// The window has tab groups from Firefox's old Panorama feature, which
+    /// `firefox_session_data` lists instead of the window itself.
+    pub panorama: bool,
+}
+impl WindowInfo {
+    /// Native tab groups in this window, including the tabs that aren't in
+    /// any group (if there are some) as the first item. Empty if the window
+    /// doesn't have any native groups.
+    pub fn native_group_ids(&self, open: bool, window: u32) -> Vec<NativeGroupId> {
+        if self.groups.is_empty() {
+            return Vec::new();
+        }
+        let ungrouped = self.tabs.iter().any(|tab| tab.group_id.is_none());
+        ungrouped
+            .then_some(None)
+            .into_iter()
+            .chain(self.groups.iter().map(|group| Some(group.id.clone())))
+            .map(|group| NativeGroupId {
+                open,
+                window,
+                group,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SessionTabs {
+    /// Tabs in open windows.
+    pub open: Vec<WindowInfo>,
+    /// Tabs in recently closed windows.
+    pub closed: Vec<WindowInfo>,
+    /// Containers from the profile that the session was loaded from.
+    pub containers: Containers,
+    /// The session's JSON text.
+    #[cfg(feature = "real_data")]
+    source: std::sync::Arc<[u8]>,
+}
+impl SessionTabs {
+    pub fn window(&self, open: bool, index: u32) -> Option<&WindowInfo> {
+        if open { &self.open } else { &self.closed }.get(index as usize)
+    }
+    /// `true` if some window has tab groups from Firefox's old Panorama
+    /// feature, then the session's groups aren't the same as its windows.
+    pub fn has_panorama_groups(&self) -> bool {
+        self.open
+            .iter()
+            .chain(&self.closed)
+            .any(|window| window.panorama)
+    }
+    /// Index of the window that is listed as the session's group at `index`,
+    /// `None` if the groups can't be mapped to windows.
+    pub fn window_of_group(&self, open: bool, index: u32) -> Option<u32> {
+        if self.has_panorama_groups() {
+            return None;
+        }
+        self.window(open, index).map(|_| index)
+    }
+    pub fn tab(&self, id: TabId) -> Option<&TabInfo> {
+        let window = self.window(id.open, id.window)?;
+        if id.recently_closed {
+            &window.closed_tabs
+        } else {
+            &window.tabs
+        }
+        .get(id.tab as usize)
+    }
+}
+
+/// A group of tabs that doesn't correspond to a window in the session, for
+/// example all tabs from the same domain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualGroup {
+    pub name: String,
+    pub tabs: Vec<TabId>,
+}
+
+impl SessionTabs {
+    /// Regroup the tabs in either the open or the closed windows. Tabs with
+    /// the same key end up in the same group and the groups are sorted by
+    /// their keys.
+    pub fn group_by(
+        &self,
+        open: bool,
+        mut key: impl FnMut(&TabInfo) -> String,
+    ) -> Vec<VirtualGroup> {
+        let windows = if open { &self.open } else { &self.closed };
+        let mut groups = BTreeMap::<String, Vec<TabId>>::new();
+        for tab in windows.iter().flat_map(|window| &window.tabs) {
+            groups.entry(key(tab)).or_default().push(tab.id);
+        }
+        groups
+            .into_iter()
+            .map(|(name, tabs)| VirtualGroup { name, tabs })
+            .collect()
+    }
+}
+
+impl TabInfo {
+    /// Case insensitive search in the tab's title and URL. The query should
+    /// already be lowercase.
+    pub fn matches_search(&self, query: &str) -> bool {
+        query.is_empty()
+            || self.title.to_lowercase().contains(query)
+            || self.url.to_lowercase().contains(query)
+    }
+}
+
+/// The host of a URL without any `www.` prefix.
+pub fn host(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    Some(host.strip_prefix("www.").unwrap_or(host).to_owned())
+}
+
+/// The registrable domain (eTLD+1) of a URL, for example `example.co.uk` for
+/// `https://mail.example.co.uk/`. Hosts that don't have a registrable domain,
+/// like IP addresses and `localhost`, are returned as is.
+pub fn domain(url: &str) -> Option<String> {
+    use publicsuffix::Psl;
+
+    static LIST: std::sync::OnceLock<publicsuffix::List> = std::sync::OnceLock::new();
+
+    let url = url::Url::parse(url).ok()?;
+    let host = match url.host()? {
+        url::Host::Domain(host) => host,
+        ip => return Some(ip.to_string()),
+    };
+    let list = LIST.get_or_init(|| {
+        include_str!("../data/public_suffix_list.dat")
+            .parse()
+            .unwrap_or_else(|e| {
+                log::error!("Failed to parse the public suffix list: {e}");
+                publicsuffix::List::new()
+            })
+    });
+    let domain = list
+        .domain(host.as_bytes())
+        .and_then(|domain| std::str::from_utf8(domain.as_bytes()).ok())
+        .unwrap_or(host);
+    Some(domain.to_owned())
+}
+
+/// Markdown link that has the tab's title as text.
+pub fn markdown_link(title: &str, url: &str) -> String {
+    let mut escaped = String::with_capacity(title.len());
+    for c in title.chars() {
+        if matches!(c, '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    format!(
+        "[{escaped}]({})",
+        url.replace(' ', "%20").replace(')', "%29")
+    )
+}
+
+/// The subset of Firefox's session format that we care about.
+mod raw {
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Default, serde::Deserialize)]
+    #[serde(default)]
+    pub struct Session {
+        pub windows: Vec<Window>,
+        #[serde(rename = "_closedWindows")]
+        pub closed_windows: Vec<Window>,
+    }
+
+    #[derive(Debug, Default, serde::Deserialize)]
+    #[serde(default)]
+    pub struct Window {
+        pub tabs: Vec<Tab>,
+        /// One-based index of the selected tab.
+        pub selected: usize,
+        pub groups: Vec<Group>,
+        #[serde(rename = "_closedTabs")]
+        pub closed_tabs: Vec<ClosedTab>,
+        #[serde(rename = "extData")]
+        pub ext_data: WindowExtData,
+    }
+
+    #[derive(Debug, Default, serde::Deserialize)]
+    #[serde(default)]
+    pub struct WindowExtData {
+        /// JSON object with the window's Panorama tab groups.
+        #[serde(rename = "tabview-group")]
+        pub tabview_group: Option<String>,
+    }
+
+    #[derive(Debug, Default, serde::Deserialize)]
+    #[serde(default, rename_all = "camelCase")]
+    pub struct ClosedTab {
+        pub state: Tab,
+        pub closed_at: Option<i64>,
+    }
+
+    #[derive(Debug, Default, serde::Deserialize)]
+    #[serde(default)]
+    pub struct Group {
+        pub id: String,
+        pub name: String,
+        pub color: String,
+        pub collapsed: bool,
+    }
+
+    #[derive(Debug, Default, serde::Deserialize)]
+    #[serde(default, rename_all = "camelCase")]
+    pub struct Tab {
+        pub entries: Vec<Entry>,
+        /// 1-based index into `entries`.
+        pub index: Option<usize>,
+        pub last_accessed: Option<i64>,
+        pub pinned: bool,
+        pub hidden: bool,
+        pub discarded: bool,
+        pub user_context_id: u32,
+        pub group_id: Option<String>,
+        #[cfg(feature = "real_data")]
+        #[serde(rename = "extData")]
+        pub ext_data: BTreeMap<String, serde_json::Value>,
+    }
+
+    #[derive(Debug, Default, serde::Deserialize)]
+    #[serde(default)]
+    pub struct Entry {
+        pub url: String,
+        pub title: Option<String>,
+    }
+}
+
+fn tab_info(id: TabId, tab: raw::Tab) -> TabInfo {
+    let current = tab
+        .index
+        .unwrap_or(tab.entries.len())
+        .clamp(1, tab.entries.len().max(1))
+        - 1;
+    let history = tab
+        .entries
+        .into_iter()
+        .map(|entry| HistoryEntry {
+            title: entry
+                .title
+                .filter(|title| !title.is_empty())
+                .unwrap_or_else(|| entry.url.clone()),
+            url: entry.url,
+        })
+        .collect::<Vec<_>>();
+    let HistoryEntry { title, url } = history.get(current).cloned().unwrap_or_default();
+    TabInfo {
+        id,
+        title,
+        url,
+        last_accessed: tab.last_accessed,
+        pinned: tab.pinned,
+        hidden: tab.hidden,
+        selected: false,
+        discarded: tab.discarded,
+        user_context_id: tab.user_context_id,
+        group_id: tab.group_id,
+        history,
+        history_index: current,
+        closed_at: None,
+        #[cfg(feature = "real_data")]
+        tree: TabTree::from_ext_data(&tab.ext_data),
+        #[cfg(not(feature = "real_data"))]
+        tree: TabTree::default(),
+    }
+}
+
+fn windows_info(open: bool, windows: Vec<raw::Window>) -> Vec<WindowInfo> {
+    windows
+        .into_iter()
+        .enumerate()
+        .map(|(window, info)| {
+            let tabs = info
+                .tabs
+                .into_iter()
+                .enumerate()
+                .map(|(tab, tab_state)| {
+                    let id = TabId {
+                        open,
+                        window: window as u32,
+                        recently_closed: false,
+                        tab: tab as u32,
+                    };
+                    TabInfo {
+                        selected: tab + 1 == info.selected,
+                        ..tab_info(id, tab_state)
+                    }
+                })
+                .collect::<Vec<_>>();
+            // Only open windows remember their closed tabs in a useful way:
+            let closed_tabs = info
+                .closed_tabs
+                .into_iter()
+                .filter(|_| open)
+                .enumerate()
+                .map(|(tab, info)| {
+                    let id = TabId {
+                        open,
+                        window: window as u32,
+                        recently_closed: true,
+                        tab: tab as u32,
+                    };
+                    TabInfo {
+                        // The tab is no longer part of its old tab group:
+                        group_id: None,
+                        closed_at: info.closed_at,
+                        ..tab_info(id, info.state)
+                    }
+                })
+                .collect::<Vec<_>>();
+            let mut groups = info
+                .groups
+                .into_iter()
+                .map(|group| NativeGroup {
+                    tabs: tabs
+                        .iter()
+                        .filter(|tab| tab.group_id.as_ref() == Some(&group.id))
+                        .map(|tab| tab.id)
+                        .collect(),
+                    id: group.id,
+                    name: group.name,
+                    color: group.color,
+                    collapsed: group.collapsed,
+                })
+                .filter(|group| !group.tabs.is_empty())
+                .collect::<Vec<_>>();
+            groups.sort_by_key(|group| group.tabs.first().copied());
+            WindowInfo {
+                tabs,
+                groups,
+                closed_tabs,
+                panorama: info
+                    .ext_data
+                    .tabview_group
+                    .is_some_and(|groups| !matches!(groups.trim(), "" | "{}")),
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "real_data")]
+impl SessionTabs {
+    pub fn from_json(source: std::sync::Arc<[u8]>) -> Result<Self, String> {
+        let session = serde_json::from_slice::<raw::Session>(&source)
+            .map_err(|e| format!("failed to read tabs from session: {e}"))?;
+        Ok(Self {
+            open: windows_info(true, session.windows),
+            closed: windows_info(false, session.closed_windows),
+            containers: Containers::default(),
+            source,
+        })
+    }
+
+    /// The session's JSON data. Only parsed when it needs to be modified, that
+    /// way the much larger JSON tree isn't kept in memory.
+    fn json(&self) -> Result<serde_json::Value, String> {
+        serde_json::from_slice(&self.source)
+            .map_err(|e| format!("failed to read tabs from session: {e}"))
+    }
+
+    /// The session's JSON data with some tabs removed.
+    pub fn json_without_tabs(
+        &self,
+        excluded: &BTreeSet<TabId>,
+    ) -> Result<serde_json::Value, String> {
+        let mut json = self.json()?;
+        for (open, key) in [(true, "windows"), (false, "_closedWindows")] {
+            let Some(windows) = json.get_mut(key).and_then(|v| v.as_array_mut()) else {
+                continue;
+            };
+            for (window_index, window) in windows.iter_mut().enumerate() {
+                // One-based index of the selected tab:
+                let selected = window
+                    .get("selected")
+                    .and_then(|v| v.as_u64())
+                    .map(|v| v as usize);
+                let Some(tabs) = window.get_mut("tabs").and_then(|v| v.as_array_mut()) else {
+                    continue;
+                };
+                let mut removed_before_selected = 0;
+                let mut tab_index = 0;
+                tabs.retain(|_| {
+                    let id = TabId {
+                        open,
+                        window: window_index as u32,
+                        recently_closed: false,
+                        tab: tab_index,
+                    };
+                    tab_index += 1;
+                    let keep = !excluded.contains(&id);
+                    if !keep && selected.is_some_and(|selected| (tab_index as usize) < selected) {
+                        removed_before_selected += 1;
+                    }
+                    keep
+                });
+                let tab_count = tabs.len();
+                // Keep pointing at the same tab, or the one after it if it
+                // was removed:
+                if let Some(selected) = selected {
+                    window["selected"] = (selected - removed_before_selected)
+                        .min(tab_count)
+                        .max(1)
+                        .into();
+                }
+            }
+        }
+        Ok(json)
+    }
+
+    /// Prefix the title of every tab that is in a container with the
+    /// container's name, that way the info is included in exported links.
+    ///
+    /// `json` should be this session's data, possibly modified by the other
+    /// methods.
+    pub fn add_container_labels(&self, json: &mut serde_json::Value) {
+        for key in ["windows", "_closedWindows"] {
+            let Some(windows) = json.get_mut(key).and_then(|v| v.as_array_mut()) else {
+                continue;
+            };
+            let tabs = windows
+                .iter_mut()
+                .filter_map(|window| window.get_mut("tabs")?.as_array_mut())
+                .flatten();
+            for tab in tabs {
+                let id = tab
+                    .get("userContextId")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or_default() as u32;
+                if id == 0 {
+                    continue;
+                }
+                let name = self.containers.name(id);
+                let Some(entries) = tab.get_mut("entries").and_then(|v| v.as_array_mut()) else {
+                    continue;
+                };
+                for entry in entries.iter_mut().filter_map(|entry| entry.as_object_mut()) {
+                    let title = match entry.get("title").and_then(|v| v.as_str()) {
+                        Some(title) if !title.is_empty() => title,
+                        _ => entry
+                            .get("url")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default(),
+                    };
+                    let title = format!("[{name}] {title}");
+                    entry.insert("title".to_owned(), title.into());
+                }
+            }
+        }
+    }
+
+    /// Replace every tab with one tab for each page in its back/forward
+    /// history. If `unique` is `true` then pages with the same URL are only
+    /// included once per tab.
+    ///
+    /// `json` should be this session's data, possibly modified by the other
+    /// methods.
+    pub fn expand_history(json: &mut serde_json::Value, unique: bool) {
+        use serde_json::Value;
+
+        for key in ["windows", "_closedWindows"] {
+            let Some(windows) = json.get_mut(key).and_then(|v| v.as_array_mut()) else {
+                continue;
+            };
+            for window in windows {
+                let Some(tabs) = window.get_mut("tabs").and_then(|v| v.as_array_mut()) else {
+                    continue;
+                };
+                *tabs = std::mem::take(tabs)
+                    .into_iter()
+                    .flat_map(|tab| {
+                        let entries = tab
+                            .get("entries")
+                            .and_then(|v| v.as_array())
+                            .cloned()
+                            .unwrap_or_default();
+                        let mut seen = BTreeSet::new();
+                        let entries = entries
+                            .into_iter()
+                            .filter(|entry| {
+                                let url = entry.get("url").and_then(|v| v.as_str());
+                                !unique || seen.insert(url.unwrap_or_default().to_owned())
+                            })
+                            .collect::<Vec<_>>();
+                        if entries.len() <= 1 {
+                            return vec![tab];
+                        }
+                        entries
+                            .into_iter()
+                            .map(|entry| {
+                                let mut tab = tab.clone();
+                                if let Some(tab) = tab.as_object_mut() {
+                                    tab.insert("entries".to_owned(), Value::Array(vec![entry]));
+                                    tab.insert("index".to_owned(), 1.into());
+                                }
+                                tab
+                            })
+                            .collect()
+                    })
+                    .collect();
+            }
+        }
+    }
+
+    /// Clean the URL of every page in every tab.
+    ///
+    /// `json` should be this session's data, possibly modified by the other
+    /// methods.
+    pub fn clean_urls(json: &mut serde_json::Value, cleaning: UrlCleaning) {
+        for key in ["windows", "_closedWindows"] {
+            let Some(windows) = json.get_mut(key).and_then(|v| v.as_array_mut()) else {
+                continue;
+            };
+            let entries = windows
+                .iter_mut()
+                .filter_map(|window| window.get_mut("tabs")?.as_array_mut())
+                .flatten()
+                .filter_map(|tab| tab.get_mut("entries")?.as_array_mut())
+                .flatten()
+                .filter_map(|entry| entry.as_object_mut());
+            for entry in entries {
+                let Some(cleaned) = entry
+                    .get("url")
+                    .and_then(|v| v.as_str())
+                    .and_then(|url| cleaning.clean(url))
+                else {
+                    continue;
+                };
+                entry.insert("url".to_owned(), cleaned.into());
+            }
+        }
+    }
+
+    /// Sort the tabs inside each window, tabs with equal keys keep their
+    /// order.
+    ///
+    /// `json` should be this session's data, possibly modified by the other
+    /// methods.
+    pub fn sort_tabs<K: Ord>(json: &mut serde_json::Value, mut key: impl FnMut(&TabInfo) -> K) {
+        use serde::Deserialize;
+
+        for key_name in ["windows", "_closedWindows"] {
+            let Some(windows) = json.get_mut(key_name).and_then(|v| v.as_array_mut()) else {
+                continue;
+            };
+            for window in windows {
+                let Some(tabs) = window.get_mut("tabs").and_then(|v| v.as_array_mut()) else {
+                    continue;
+                };
+                let mut sorted = std::mem::take(tabs)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, tab)| {
+                        let id = TabId {
+                            open: true,
+                            window: 0,
+                            recently_closed: false,
+                            tab: index as u32,
+                        };
+                        let info = tab_info(id, raw::Tab::deserialize(&tab).unwrap_or_default());
+                        (key(&info), index, tab)
+                    })
+                    .collect::<Vec<_>>();
+                sorted.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+
+                // Keep the same tab selected:
+                let selected =
+                    window
+                        .get("selected")
+                        .and_then(|v| v.as_u64())
+                        .and_then(|selected| {
+                            sorted
+                                .iter()
+                                .position(|(_, index, _)| *index as u64 + 1 == selected)
+                        });
+                if let Some(selected) = selected {
+                    window["selected"] = (selected + 1).into();
+                }
+                window["tabs"] = sorted.into_iter().map(|(.., tab)| tab).collect();
+            }
+        }
+    }
+
+    /// The session's JSON data with the tabs rearranged into virtual groups.
+    ///
+    /// `firefox_session_data` only writes a section for each window, or for
+    /// each tab group of the old Panorama feature (stored in each window's
+    /// `extData`) when a window has such groups. To get a section for each
+    /// virtual group, the selected tabs are moved into a copy of the first
+    /// window and each virtual group is written as a Panorama group of that
+    /// window. So the result has a single open window and a single closed
+    /// window, and the data of the other windows (and the first window's own
+    /// `extData`) is lost. It is only meant to be converted to links, not to be
+    /// used as a session.
+    pub fn json_with_groups(
+        &self,
+        open: &[VirtualGroup],
+        closed: &[VirtualGroup],
+        excluded: &BTreeSet<TabId>,
+    ) -> Result<serde_json::Value, String> {
+        use serde_json::{Value, json};
+
+        let mut json = self.json()?;
+        for (open, groups, key) in [(true, open, "windows"), (false, closed, "_closedWindows")] {
+            let Some(windows) = json.get_mut(key).and_then(|v| v.as_array_mut()) else {
+                continue;
+            };
+            let tab_json = |id: TabId| -> Option<Value> {
+                let window = windows.get(id.window as usize)?;
+                let tab = if id.recently_closed {
+                    window
+                        .get("_closedTabs")?
+                        .get(id.tab as usize)?
+                        .get("state")?
+                } else {
+                    window.get("tabs")?.get(id.tab as usize)?
+                };
+                (id.open == open).then(|| tab.clone())
+            };
+
+            let mut group_info = serde_json::Map::new();
+            let mut tabs = Vec::new();
+            let groups = groups
+                .iter()
+                .filter(|group| group.tabs.iter().any(|id| !excluded.contains(id)));
+            for (group_id, group) in (1_u32..).zip(groups) {
+                group_info.insert(
+                    group_id.to_string(),
+                    json!({ "id": group_id, "title": group.name }),
+                );
+                for &id in group.tabs.iter().filter(|id| !excluded.contains(id)) {
+                    let Some(mut tab) = tab_json(id) else {
+                        continue;
+                    };
+                    if let Some(tab) = tab.as_object_mut() {
+                        let ext_data = tab.entry("extData").or_insert_with(|| json!({}));
+                        if let Some(ext_data) = ext_data.as_object_mut() {
+                            ext_data.insert(
+                                "tabview-tab".to_owned(),
+                                json!({ "groupID": group_id }).to_string().into(),
+                            );
+                        }
+                    }
+                    tabs.push(tab);
+                }
+            }
+
+            let Some(mut window) = windows.first().cloned().filter(|_| !tabs.is_empty()) else {
+                windows.clear();
+                continue;
+            };
+            let next_id = group_info.len() + 1;
+            if let Some(window) = window.as_object_mut() {
+                window.insert("selected".to_owned(), 1.into());
+                window.insert("tabs".to_owned(), tabs.into());
+                window.insert(
+                    "extData".to_owned(),
+                    json!({
+                        "tabview-group": Value::from(group_info).to_string(),
+                        "tabview-groups": json!({
+                            "nextID": next_id,
+                            "activeGroupId": 1,
+                        })
+                        .to_string(),
+                    }),
+                );
+            }
+            *windows = vec![window];
+        }
+        Ok(json)
+    }
+}
+
+/// Fake tabs that are shown when not reading real session data.
+#[cfg(not(feature = "real_data"))]
+impl SessionTabs {
+    pub fn fake() -> Self {
+        let window = |open: bool, window: u32, count: u32| WindowInfo {
+            tabs: (0..count)
+                .map(|tab| TabInfo {
+                    id: TabId {
+                        open,
+                        window,
+                        recently_closed: false,
+                        tab,
+                    },
+                    title: format!("Example Domain {}", tab + 1),
+                    url: format!("http://www.example.com/{}", tab + 1),
+                    last_accessed: None,
+                    pinned: tab == 0,
+                    hidden: false,
+                    selected: tab == 0,
+                    discarded: tab > 1,
+                    user_context_id: window,
+                    group_id: (open && window == 0 && tab > 0).then(|| "research".to_owned()),
+                    history: (0..=tab)
+                        .map(|page| HistoryEntry {
+                            title: format!("Example Domain {}", page + 1),
+                            url: format!("http://www.example.com/{}", page + 1),
+                        })
+                        .collect(),
+                    history_index: tab as usize,
+                    closed_at: None,
+                    tree: TabTree::default(),
+                })
+                .collect(),
+            groups: Vec::new(),
+            closed_tabs: Vec::new(),
+            panorama: false,
+        };
+        let mut containers = Containers::default();
+        containers.insert(
+            1,
+            crate::containers::Container {
+                name: "Personal".into(),
+                color: "blue".into(),
+            },
+        );
+        let mut first_window = window(true, 0, 3);
+        first_window.groups.push(NativeGroup {
+            id: "research".into(),
+            name: "Research".into(),
+            color: "green".into(),
+            collapsed: false,
+            tabs: first_window.tabs[1..].iter().map(|tab| tab.id).collect(),
+        });
+        first_window.closed_tabs = (0..2)
+            .map(|tab| TabInfo {
+                id: TabId {
+                    open: true,
+                    window: 0,
+                    recently_closed: true,
+                    tab,
+                },
+                title: format!("Closed Example {}", tab + 1),
+                url: format!("http://www.example.org/{}", tab + 1),
+                last_accessed: None,
+                pinned: false,
+                hidden: false,
+                selected: false,
+                discarded: true,
+                user_context_id: 0,
+                group_id: None,
+                history: vec![HistoryEntry {
+                    title: format!("Closed Example {}", tab + 1),
+                    url: format!("http://www.example.org/{}", tab + 1),
+                }],
+                history_index: 0,
+                closed_at: Some(1_700_000_000_000 - i64::from(tab) * 3_600_000),
+                tree: TabTree::default(),
+            })
+            .collect();
+        Self {
+            open: vec![first_window, window(true, 1, 2)],
+            closed: vec![window(false, 0, 1)],
+            containers,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "real_data"))]
+mod tests {
+    use super::*;
+
+    fn session(json: serde_json::Value) -> SessionTabs {
+        SessionTabs::from_json(json.to_string().into_bytes().into()).unwrap()
+    }
+
+    fn tab(url: &str) -> serde_json::Value {
+        serde_json::json!({ "entries": [{ "url": url, "title": url }], "index": 1 })
+    }
+
+    #[test]
+    fn domain_is_registrable_domain() {
+        assert_eq!(
+            domain("https://www.example.co.uk/a").as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(
+            domain("https://mail.google.com").as_deref(),
+            Some("google.com")
+        );
+        // Public suffixes from the private section count as well:
+        assert_eq!(
+            domain("https://user.github.io/page").as_deref(),
+            Some("user.github.io")
+        );
+        assert_eq!(
+            domain("http://localhost:8080/").as_deref(),
+            Some("localhost")
+        );
+        assert_eq!(domain("http://127.0.0.1/").as_deref(), Some("127.0.0.1"));
+        assert_eq!(domain("about:blank"), None);
+    }
+
+    #[test]
+    fn host_keeps_subdomains() {
+        assert_eq!(
+            host("https://www.mail.example.com/").as_deref(),
+            Some("mail.example.com")
+        );
+        assert_eq!(host("about:blank"), None);
+    }
+
+    #[test]
+    fn groups_are_windows_without_panorama() {
+        let tabs = session(serde_json::json!({
+            "windows": [{ "tabs": [tab("https://a.com")] }, { "tabs": [tab("https://b.com")] }],
+            "_closedWindows": [{ "tabs": [tab("https://c.com")] }],
+        }));
+        assert!(!tabs.has_panorama_groups());
+        assert_eq!(tabs.window_of_group(true, 1), Some(1));
+        assert_eq!(tabs.window_of_group(false, 0), Some(0));
+        // Groups after the windows, for example recently closed tabs:
+        assert_eq!(tabs.window_of_group(true, 2), None);
+    }
+
+    #[test]
+    fn panorama_groups_are_not_windows() {
+        let tabs = session(serde_json::json!({
+            "windows": [
+                { "tabs": [tab("https://a.com")] },
+                {
+                    "tabs": [tab("https://b.com")],
+                    "extData": { "tabview-group": r#"{"1":{"id":1,"title":"Work"}}"# },
+                },
+            ],
+        }));
+        assert!(tabs.has_panorama_groups());
+        assert_eq!(tabs.window_of_group(true, 0), None);
+    }
+
+    #[test]
+    fn tree_data_is_read_from_ext_data() {
+        let tabs = session(serde_json::json!({
+            "windows": [{ "tabs": [
+                {
+                    "entries": [],
+                    "extData": {
+                        "extension:{3c078156-979c-498b-8990-85f7987dd929}:data":
+                            r#"{"id":5,"panelId":"p","parentId":-1}"#,
+                        "treestyletab-id": "tab-1",
+                        "treestyletab-parent": "",
+                    },
+                },
+                {
+                    "entries": [],
+                    "extData": {
+                        "extension:{3c078156-979c-498b-8990-85f7987dd929}:data":
+                            r#"{"id":6,"panelId":"p","parentId":5}"#,
+                        "extension:treestyletab@piro.sakura.ne.jp:data-persistent-id":
+                            r#"{"id":"tab-2"}"#,
+                        "extension:treestyletab@piro.sakura.ne.jp:ancestors":
+                            r#"["tab-1","tab-0"]"#,
+                    },
+                },
+            ] }],
+        }));
+        let node = |id: &str, parent: Option<&str>| TreeNode {
+            id: id.to_owned(),
+            parent: parent.map(str::to_owned),
+        };
+        let [first, second] = &tabs.open[0].tabs[..] else {
+            panic!("expected two tabs");
+        };
+        assert_eq!(
+            first.tree,
+            TabTree {
+                sidebery: Some(node("5", None)),
+                tst: None,
+                tst_legacy: Some(node("tab-1", None)),
+            }
+        );
+        assert_eq!(second.tree.sidebery, Some(node("6", Some("5"))));
+        assert_eq!(second.tree.tst, Some(node("tab-2", Some("tab-1"))));
+    }
+
+    #[test]
+    fn excluded_tabs_are_removed() {
+        let tabs = session(serde_json::json!({
+            "windows": [{
+                "tabs": [tab("https://a.com"), tab("https://b.com"), tab("https://c.com")],
+                "selected": 3,
+            }],
+        }));
+        let excluded = [tabs.open[0].tabs[2].id, tabs.open[0].tabs[0].id].into();
+        let json = tabs.json_without_tabs(&excluded).unwrap();
+        let window = &json["windows"][0];
+        assert_eq!(window["tabs"].as_array().unwrap().len(), 1);
+        assert_eq!(window["tabs"][0]["entries"][0]["url"], "https://b.com");
+        assert_eq!(window["selected"], 1);
+    }
+
+    #[test]
+    fn selected_tab_stays_selected() {
+        let tabs = session(serde_json::json!({
+            "windows": [{
+                "tabs": [
+                    tab("https://a.com"),
+                    tab("https://b.com"),
+                    tab("https://c.com"),
+                    tab("https://d.com"),
+                ],
+                "selected": 3,
+            }],
+        }));
+        let excluded = [tabs.open[0].tabs[0].id, tabs.open[0].tabs[3].id].into();
+        let json = tabs.json_without_tabs(&excluded).unwrap();
+        assert_eq!(json["windows"][0]["selected"], 2);
+        assert_eq!(
+            json["windows"][0]["tabs"][1]["entries"][0]["url"],
+            "https://c.com"
+        );
+
+        // The tab after a removed selected tab takes its place:
+        let excluded = [tabs.open[0].tabs[0].id, tabs.open[0].tabs[2].id].into();
+        let json = tabs.json_without_tabs(&excluded).unwrap();
+        assert_eq!(json["windows"][0]["selected"], 2);
+        assert_eq!(
+            json["windows"][0]["tabs"][1]["entries"][0]["url"],
+            "https://d.com"
+        );
+    }
+}
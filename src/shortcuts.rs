@@ -0,0 +1,102 @@
+//! Keyboard shortcuts and the dialog that lists them.
+
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+use crate::i18n::{self, tr};
+
+pub const OPEN_FILE: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::O);
+#[cfg(not(target_family = "wasm"))]
+pub const RELOAD: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::F5);
+/// Browsers reload the page on F5 (and Ctrl+R) before the app sees the key,
+/// which would lose the loaded session.
+#[cfg(target_family = "wasm")]
+pub const RELOAD: KeyboardShortcut = KeyboardShortcut::new(Modifiers::ALT, Key::R);
+pub const SAVE: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::S);
+/// Copies the selected tabs in the tab list. It arrives as a copy event, so
+/// this is only used to show the shortcut.
+pub const COPY_TABS: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::C);
+pub const COPY_LINKS: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::C);
+pub const FOCUS_SEARCH: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::F);
+pub const COMMAND_PALETTE: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::P);
+pub const CHEAT_SHEET: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::F1);
+pub const UNDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+pub const REDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Y);
+/// Alternative to [`REDO`] that is common on macOS.
+pub const REDO_SHIFTED: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+
+/// Shortcuts that work everywhere and the message ids of their descriptions,
+/// in the order they are listed in the cheat sheet.
+const GLOBAL: &[(KeyboardShortcut, &str)] = &[
+    (OPEN_FILE, "shortcut-open-file"),
+    (RELOAD, "shortcut-reload"),
+    (SAVE, "save-links"),
+    (COPY_LINKS, "copy-links"),
+    (FOCUS_SEARCH, "shortcut-search"),
+    (COMMAND_PALETTE, "shortcut-command-palette"),
+    (UNDO, "shortcut-undo"),
+    (REDO, "shortcut-redo"),
+    (egui::gui_zoom::kb_shortcuts::ZOOM_IN, "shortcut-zoom-in"),
+    (egui::gui_zoom::kb_shortcuts::ZOOM_OUT, "shortcut-zoom-out"),
+    (
+        egui::gui_zoom::kb_shortcuts::ZOOM_RESET,
+        "shortcut-zoom-reset",
+    ),
+    (CHEAT_SHEET, "shortcut-cheat-sheet"),
+];
+
+/// Message ids of the keys that are used in the window list after it was
+/// clicked and of their descriptions.
+const WINDOW_LIST: &[(&str, &str)] = &[
+    ("key-up-down", "shortcut-move"),
+    ("key-space", "shortcut-toggle"),
+    ("key-shift-space", "shortcut-select-range"),
+    ("key-escape", "shortcut-escape"),
+];
+
+/// A menu button that shows its keyboard shortcut.
+pub fn button<'a>(
+    ui: &egui::Ui,
+    text: impl egui::IntoAtoms<'a>,
+    shortcut: KeyboardShortcut,
+) -> egui::Button<'a> {
+    egui::Button::new(text).shortcut_text(ui.format_shortcut(&shortcut))
+}
+
+/// Native builds report Ctrl+Shift+C as a copy event instead of a key press,
+/// so [`COPY_LINKS`] also consumes copy events while Shift is held.
+pub fn consume_shifted_copy(input: &mut egui::InputState) -> bool {
+    if !input.modifiers.shift {
+        return false;
+    }
+    let len = input.events.len();
+    input.events.retain(|event| *event != egui::Event::Copy);
+    input.events.len() != len
+}
+
+/// List all keyboard shortcuts.
+pub fn cheat_sheet_ui(ui: &mut egui::Ui) {
+    egui::Grid::new("keyboard_shortcuts")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            for (shortcut, description) in GLOBAL {
+                ui.strong(ui.ctx().format_shortcut(shortcut));
+                ui.label(i18n::message(description, &[]));
+                ui.end_row();
+            }
+        });
+    ui.add_space(8.0);
+    ui.label(tr!("shortcut-window-list"));
+    egui::Grid::new("window_list_shortcuts")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            for (keys, description) in WINDOW_LIST {
+                ui.strong(i18n::message(keys, &[]));
+                ui.label(i18n::message(description, &[]));
+                ui.end_row();
+            }
+        });
+}
@@ -0,0 +1,419 @@
+//! Statistics about the tabs in a loaded session.
+
+use std::collections::HashMap;
+
+use egui::Widget;
+
+use crate::{
+    host::AllTabGroups,
+    i18n::tr,
+    session::{SessionTabs, TabInfo, TreeNode, domain},
+};
+
+/// Number of entries to show in "top N" style lists.
+const TOP_COUNT: usize = 15;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowStatistics {
+    pub name: String,
+    pub open: bool,
+    pub tabs: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OldTab {
+    pub title: String,
+    pub url: String,
+    /// Milliseconds since the Unix epoch.
+    pub last_accessed: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Statistics {
+    pub windows: Vec<WindowStatistics>,
+    pub total_tabs: usize,
+    pub pinned_tabs: usize,
+    pub hidden_tabs: usize,
+    pub discarded_tabs: usize,
+    /// Domains sorted by how many tabs they have.
+    pub top_domains: Vec<(String, usize)>,
+    /// Tabs that haven't been accessed for the longest time.
+    pub oldest_tabs: Vec<OldTab>,
+    /// Number of tabs in each container.
+    pub containers: Vec<(String, usize)>,
+    /// Number of tabs at each depth in the tab tree, the first item is for
+    /// top level tabs.
+    pub tree_depths: Vec<usize>,
+}
+
+/// Count how often each key occurs and sort by the most common.
+fn count_sorted<K: Ord + std::hash::Hash>(keys: impl Iterator<Item = K>) -> Vec<(K, usize)> {
+    let mut counts = HashMap::<K, usize>::new();
+    for key in keys {
+        *counts.entry(key).or_default() += 1;
+    }
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by(|(a_key, a), (b_key, b)| b.cmp(a).then_with(|| a_key.cmp(b_key)));
+    counts
+}
+
+fn tree_node(tab: &TabInfo, source: Option<usize>) -> Option<&TreeNode> {
+    tab.tree.sources()[source?]
+}
+
+/// Number of tabs at each depth of the tab tree. Like when converting the
+/// session to links only the first extension that has tree data is used.
+fn tree_depths(tabs: &SessionTabs) -> Vec<usize> {
+    let windows = || tabs.open.iter().chain(&tabs.closed);
+    let source = (0..3).find(|&source| {
+        windows()
+            .flat_map(|window| &window.tabs)
+            .any(|tab| tab.tree.sources()[source].is_some())
+    });
+    let mut tree_depths = Vec::new();
+    for window in windows() {
+        let parents = window
+            .tabs
+            .iter()
+            .filter_map(|tab| tree_node(tab, source))
+            .map(|node| (node.id.as_str(), node.parent.as_deref()))
+            .collect::<HashMap<_, _>>();
+        for tab in &window.tabs {
+            let mut depth = 0;
+            let mut parent = tree_node(tab, source).and_then(|node| node.parent.as_deref());
+            // Parents in other windows are ignored and the depth is limited in
+            // case the data has a cycle:
+            while let Some(id) = parent
+                && depth < window.tabs.len()
+                && let Some(&next) = parents.get(id)
+            {
+                depth += 1;
+                parent = next;
+            }
+            if tree_depths.len() <= depth {
+                tree_depths.resize(depth + 1, 0);
+            }
+            tree_depths[depth] += 1;
+        }
+    }
+    tree_depths
+}
+
+/// Calculate statistics for a session.
+pub fn compute(tabs: &SessionTabs, groups: &AllTabGroups) -> Statistics {
+    let all_tabs = || tabs.open.iter().chain(&tabs.closed).flat_map(|w| &w.tabs);
+
+    let windows = [(true, &tabs.open), (false, &tabs.closed)]
+        .into_iter()
+        .flat_map(|(open, windows)| {
+            (0_u32..)
+                .zip(windows)
+                .map(move |(index, window)| WindowStatistics {
+                    name: groups.group_of_window(open, index).map_or_else(
+                        || tr!("window-number", number = index + 1),
+                        |g| g.name.clone(),
+                    ),
+                    open,
+                    tabs: window.tabs.len(),
+                })
+        })
+        .collect();
+
+    let mut top_domains = count_sorted(all_tabs().filter_map(|tab| domain(&tab.url)));
+    top_domains.truncate(TOP_COUNT);
+
+    let mut oldest_tabs = all_tabs()
+        .filter_map(|tab: &TabInfo| {
+            Some(OldTab {
+                title: tab.title.clone(),
+                url: tab.url.clone(),
+                last_accessed: tab.last_accessed.filter(|&time| time > 0)?,
+            })
+        })
+        .collect::<Vec<_>>();
+    oldest_tabs.sort_by_key(|tab| tab.last_accessed);
+    oldest_tabs.truncate(TOP_COUNT);
+
+    Statistics {
+        windows,
+        total_tabs: all_tabs().count(),
+        pinned_tabs: all_tabs().filter(|tab| tab.pinned).count(),
+        hidden_tabs: all_tabs().filter(|tab| tab.hidden).count(),
+        discarded_tabs: all_tabs().filter(|tab| tab.discarded).count(),
+        top_domains,
+        oldest_tabs,
+        containers: count_sorted(all_tabs().map(|tab| tabs.containers.name(tab.user_context_id))),
+        tree_depths: tree_depths(tabs),
+    }
+}
+
+/// Format a timestamp in milliseconds as a local date.
+pub fn format_date(millis: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// Format a timestamp in milliseconds as a local date and time.
+pub fn format_date_time(millis: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn count_grid(ui: &mut egui::Ui, id: &str, rows: impl IntoIterator<Item = (String, usize)>) {
+    egui::Grid::new(id).striped(true).show(ui, |ui| {
+        for (label, count) in rows {
+            egui::Label::new(label).truncate().ui(ui);
+            ui.label(count.to_string());
+            ui.end_row();
+        }
+    });
+}
+
+pub fn show(ui: &mut egui::Ui, stats: &Statistics) {
+    count_grid(
+        ui,
+        "statistics_totals",
+        [
+            (tr!("statistics-windows"), stats.windows.len()),
+            (tr!("tabs"), stats.total_tabs),
+            (tr!("statistics-pinned-tabs"), stats.pinned_tabs),
+            (tr!("statistics-hidden-tabs"), stats.hidden_tabs),
+            (tr!("statistics-discarded-tabs"), stats.discarded_tabs),
+        ],
+    );
+
+    egui::CollapsingHeader::new(tr!("statistics-tabs-per-window"))
+        .id_salt("statistics_tabs_per_window")
+        .default_open(true)
+        .show(ui, |ui| {
+            count_grid(
+                ui,
+                "statistics_windows",
+                stats.windows.iter().map(|window| {
+                    let name = if window.open {
+                        window.name.clone()
+                    } else {
+                        tr!("statistics-closed-window", name = window.name)
+                    };
+                    (name, window.tabs)
+                }),
+            );
+        });
+
+    egui::CollapsingHeader::new(tr!("statistics-top-domains"))
+        .id_salt("statistics_top_domains")
+        .default_open(true)
+        .show(ui, |ui| {
+            count_grid(ui, "statistics_domains", stats.top_domains.iter().cloned());
+        });
+
+    egui::CollapsingHeader::new(tr!("statistics-oldest-tabs"))
+        .id_salt("statistics_oldest_tabs")
+        .show(ui, |ui| {
+            egui::Grid::new("statistics_oldest")
+                .striped(true)
+                .show(ui, |ui| {
+                    for tab in &stats.oldest_tabs {
+                        ui.label(format_date(tab.last_accessed));
+                        egui::Hyperlink::from_label_and_url(&tab.title, &tab.url)
+                            .open_in_new_tab(true)
+                            .ui(ui)
+                            .on_hover_text(&tab.url);
+                        ui.end_row();
+                    }
+                });
+        });
+
+    egui::CollapsingHeader::new(tr!("statistics-containers"))
+        .id_salt("statistics_containers")
+        .show(ui, |ui| {
+            count_grid(
+                ui,
+                "statistics_containers",
+                stats.containers.iter().cloned(),
+            );
+        });
+
+    egui::CollapsingHeader::new(tr!("statistics-tree-depth"))
+        .id_salt("statistics_tree_depth")
+        .show(ui, |ui| {
+            count_grid(
+                ui,
+                "statistics_tree_depth",
+                stats
+                    .tree_depths
+                    .iter()
+                    .enumerate()
+                    .map(|(depth, &count)| (tr!("statistics-depth", depth = depth), count)),
+            );
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        host::TabGroup,
+        session::{TabId, TabTree, WindowInfo},
+    };
+
+    fn tab(window: u32, tab: u32, url: &str) -> TabInfo {
+        TabInfo {
+            id: TabId {
+                open: true,
+                window,
+                recently_closed: false,
+                tab,
+            },
+            title: url.to_owned(),
+            url: url.to_owned(),
+            last_accessed: None,
+            pinned: false,
+            hidden: false,
+            selected: false,
+            discarded: false,
+            user_context_id: 0,
+            group_id: None,
+            history: Vec::new(),
+            history_index: 0,
+            closed_at: None,
+            tree: TabTree::default(),
+        }
+    }
+
+    fn session() -> SessionTabs {
+        let mut tabs = SessionTabs::default();
+        tabs.open = vec![
+            WindowInfo {
+                tabs: vec![
+                    TabInfo {
+                        pinned: true,
+                        last_accessed: Some(3_000),
+                        ..tab(0, 0, "https://www.example.com/")
+                    },
+                    TabInfo {
+                        hidden: true,
+                        last_accessed: Some(1_000),
+                        ..tab(0, 1, "https://example.com/a")
+                    },
+                    TabInfo {
+                        last_accessed: Some(0),
+                        ..tab(0, 2, "https://rust-lang.org/")
+                    },
+                ],
+                ..Default::default()
+            },
+            WindowInfo {
+                tabs: vec![TabInfo {
+                    discarded: true,
+                    last_accessed: Some(2_000),
+                    ..tab(1, 0, "about:blank")
+                }],
+                ..Default::default()
+            },
+        ];
+        tabs
+    }
+
+    fn group(index: u32, name: &str, window: Option<u32>) -> TabGroup {
+        TabGroup {
+            index,
+            name: name.to_owned(),
+            window,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn counts_tabs() {
+        let stats = compute(&session(), &AllTabGroups::default());
+        assert_eq!(stats.total_tabs, 4);
+        assert_eq!(stats.pinned_tabs, 1);
+        assert_eq!(stats.hidden_tabs, 1);
+        assert_eq!(stats.discarded_tabs, 1);
+        assert_eq!(
+            stats.top_domains,
+            vec![
+                ("example.com".to_owned(), 2),
+                ("rust-lang.org".to_owned(), 1)
+            ]
+        );
+        // Tabs without a valid access time are skipped:
+        assert_eq!(
+            stats
+                .oldest_tabs
+                .iter()
+                .map(|tab| tab.last_accessed)
+                .collect::<Vec<_>>(),
+            vec![1_000, 2_000, 3_000]
+        );
+        assert_eq!(stats.containers.len(), 1);
+        assert_eq!(stats.containers[0].1, 4);
+    }
+
+    #[test]
+    fn window_names_come_from_the_group_of_each_window() {
+        // Sorted groups, so the first group lists the second window:
+        let groups = AllTabGroups {
+            open: vec![
+                group(1, "Second", Some(1)),
+                group(0, "First", Some(0)),
+                group(2, "Closed tabs", None),
+            ],
+            closed: Vec::new(),
+        };
+        let stats = compute(&session(), &groups);
+        let windows = stats
+            .windows
+            .iter()
+            .map(|window| (window.name.as_str(), window.tabs))
+            .collect::<Vec<_>>();
+        assert_eq!(windows, vec![("First", 3), ("Second", 1)]);
+
+        // Groups that aren't windows can't be used:
+        let groups = AllTabGroups {
+            open: vec![group(0, "Panorama group", None)],
+            closed: Vec::new(),
+        };
+        let stats = compute(&session(), &groups);
+        assert_eq!(stats.windows[0].name, "Window 1");
+    }
+
+    #[test]
+    fn tree_depths_from_parent_ids() {
+        let node = |id: &str, parent: Option<&str>| TreeNode {
+            id: id.to_owned(),
+            parent: parent.map(str::to_owned),
+        };
+        let mut tabs = session();
+        let window = &mut tabs.open[0].tabs;
+        window[0].tree.tst_legacy = Some(node("a", None));
+        window[1].tree.tst_legacy = Some(node("b", Some("a")));
+        window[2].tree.tst_legacy = Some(node("c", Some("b")));
+        let stats = compute(&tabs, &AllTabGroups::default());
+        assert_eq!(stats.tree_depths, vec![2, 1, 1]);
+
+        // Sidebery data is preferred, a parent that isn't found is ignored:
+        tabs.open[1].tabs[0].tree.sidebery = Some(node("1", Some("missing")));
+        let stats = compute(&tabs, &AllTabGroups::default());
+        assert_eq!(stats.tree_depths, vec![4]);
+
+        // Cycles don't hang:
+        let mut tabs = session();
+        let window = &mut tabs.open[0].tabs;
+        window[0].tree.tst = Some(node("a", Some("b")));
+        window[1].tree.tst = Some(node("b", Some("a")));
+        let stats = compute(&tabs, &AllTabGroups::default());
+        assert_eq!(stats.tree_depths.iter().sum::<usize>(), 4);
+    }
+}
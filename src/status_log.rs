@@ -0,0 +1,301 @@
+//! History of the status messages that are shown in the status bar, so that
+//! earlier errors can still be read after newer messages replaced them.
+
+use std::collections::VecDeque;
+
+use egui::Widget;
+
+use crate::i18n::tr;
+
+/// Number of events that are kept, older events are forgotten.
+const MAX_EVENTS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusLevel {
+    /// Work that has started but not finished yet. Only kept in the log until
+    /// the next event of the same operation arrives.
+    Progress,
+    Info,
+    Error,
+}
+impl StatusLevel {
+    pub fn label(self) -> String {
+        match self {
+            StatusLevel::Progress => tr!("log-level-progress"),
+            StatusLevel::Info => tr!("log-level-info"),
+            StatusLevel::Error => tr!("log-level-error"),
+        }
+    }
+}
+
+/// What the app was doing when a status event was created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Load,
+    Grouping,
+    Preview,
+    Statistics,
+    Clipboard,
+    Save,
+}
+impl Operation {
+    pub fn label(self) -> String {
+        match self {
+            Operation::Load => tr!("operation-load"),
+            Operation::Grouping => tr!("operation-grouping"),
+            Operation::Preview => tr!("operation-preview"),
+            Operation::Statistics => tr!("operation-statistics"),
+            Operation::Clipboard => tr!("operation-clipboard"),
+            Operation::Save => tr!("operation-save"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusEvent {
+    pub time: chrono::DateTime<chrono::Local>,
+    pub level: StatusLevel,
+    pub operation: Operation,
+    pub message: String,
+    /// Number of times the same event happened in a row.
+    pub repeated: usize,
+}
+impl StatusEvent {
+    pub fn new(level: StatusLevel, operation: Operation, message: String) -> Self {
+        Self {
+            time: chrono::Local::now(),
+            level,
+            operation,
+            message,
+            repeated: 1,
+        }
+    }
+    pub fn progress(operation: Operation, message: String) -> Self {
+        Self::new(StatusLevel::Progress, operation, message)
+    }
+    pub fn info(operation: Operation, message: String) -> Self {
+        Self::new(StatusLevel::Info, operation, message)
+    }
+    pub fn error(operation: Operation, message: String) -> Self {
+        Self::new(StatusLevel::Error, operation, message)
+    }
+
+    fn is_repeat_of(&self, other: &Self) -> bool {
+        self.level == other.level
+            && self.operation == other.operation
+            && self.message == other.message
+    }
+
+    /// The message together with how many times it was repeated.
+    pub fn text(&self) -> String {
+        if self.repeated > 1 {
+            format!("{} (×{})", self.message, self.repeated)
+        } else {
+            self.message.clone()
+        }
+    }
+
+    /// Format the event as a single line of text.
+    pub fn to_line(&self) -> String {
+        format!(
+            "{} [{}] {}: {}",
+            self.time.format("%Y-%m-%d %H:%M:%S"),
+            self.level.label(),
+            self.operation.label(),
+            self.text()
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct StatusLog {
+    events: VecDeque<StatusEvent>,
+}
+impl StatusLog {
+    /// Add an event. It replaces the latest event if that is the progress of
+    /// the same operation.
+    pub fn push(&mut self, event: StatusEvent) {
+        if let Some(last) = self.events.back_mut()
+            && last.is_repeat_of(&event)
+        {
+            last.time = event.time;
+            last.repeated += 1;
+            return;
+        }
+        self.finish(event.operation);
+        if self.events.len() >= MAX_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    /// Remove the latest event if it is the progress of this operation, for
+    /// operations that finished without anything worth logging.
+    pub fn finish(&mut self, operation: Operation) {
+        if self
+            .events
+            .back()
+            .is_some_and(|last| last.level == StatusLevel::Progress && last.operation == operation)
+        {
+            self.events.pop_back();
+        }
+    }
+
+    /// The event that should be shown in the status bar.
+    pub fn latest(&self) -> Option<&StatusEvent> {
+        self.events.back()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|event| event.level == StatusLevel::Error)
+            .count()
+    }
+
+    /// The whole log as text, for example to include in a bug report.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for event in &self.events {
+            text.push_str(&event.to_line());
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Show all events, newest first. Returns `true` if the user asked to
+    /// copy the log.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut copy = false;
+        ui.horizontal(|ui| {
+            ui.strong(tr!("status-log"));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                copy = ui
+                    .add_enabled(!self.events.is_empty(), egui::Button::new(tr!("copy-log")))
+                    .on_hover_text(tr!("copy-log-hover"))
+                    .clicked();
+                if ui
+                    .add_enabled(!self.events.is_empty(), egui::Button::new(tr!("clear-log")))
+                    .clicked()
+                {
+                    self.events.clear();
+                }
+            });
+        });
+        ui.separator();
+        if self.events.is_empty() {
+            ui.weak(tr!("status-log-empty"));
+            return copy;
+        }
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                egui::Grid::new("status_log")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for event in self.events.iter().rev() {
+                            ui.weak(event.time.format("%H:%M:%S").to_string())
+                                .on_hover_text(event.time.format("%Y-%m-%d").to_string());
+                            let level = egui::RichText::new(event.level.label());
+                            ui.label(match event.level {
+                                StatusLevel::Progress => level.weak(),
+                                StatusLevel::Info => level,
+                                StatusLevel::Error => level.color(ui.visuals().error_fg_color),
+                            });
+                            ui.label(event.operation.label());
+                            egui::Label::new(event.text()).wrap().ui(ui);
+                            ui.end_row();
+                        }
+                    });
+            });
+        copy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levels(log: &StatusLog) -> Vec<(StatusLevel, Operation, usize)> {
+        log.events
+            .iter()
+            .map(|event| (event.level, event.operation, event.repeated))
+            .collect()
+    }
+
+    #[test]
+    fn progress_is_replaced_by_the_same_operation() {
+        let mut log = StatusLog::default();
+        log.push(StatusEvent::progress(Operation::Load, "Reading".into()));
+        log.push(StatusEvent::progress(Operation::Load, "Parsing".into()));
+        log.push(StatusEvent::info(Operation::Load, "Loaded".into()));
+        assert_eq!(levels(&log), [(StatusLevel::Info, Operation::Load, 1)]);
+    }
+
+    #[test]
+    fn progress_of_other_operations_is_kept() {
+        let mut log = StatusLog::default();
+        log.push(StatusEvent::progress(Operation::Save, "Saving".into()));
+        log.push(StatusEvent::error(Operation::Clipboard, "Failed".into()));
+        assert_eq!(
+            levels(&log),
+            [
+                (StatusLevel::Progress, Operation::Save, 1),
+                (StatusLevel::Error, Operation::Clipboard, 1),
+            ]
+        );
+        // Only the latest event is replaced:
+        log.push(StatusEvent::info(Operation::Save, "Saved".into()));
+        assert_eq!(log.events.len(), 3);
+    }
+
+    #[test]
+    fn repeated_events_are_counted() {
+        let mut log = StatusLog::default();
+        log.push(StatusEvent::error(Operation::Preview, "Failed".into()));
+        log.push(StatusEvent::error(Operation::Preview, "Failed".into()));
+        log.push(StatusEvent::error(Operation::Preview, "Other".into()));
+        assert_eq!(
+            levels(&log),
+            [
+                (StatusLevel::Error, Operation::Preview, 2),
+                (StatusLevel::Error, Operation::Preview, 1),
+            ]
+        );
+        assert_eq!(log.error_count(), 2);
+        assert_eq!(
+            log.latest().map(StatusEvent::text).as_deref(),
+            Some("Other")
+        );
+    }
+
+    #[test]
+    fn finish_only_removes_progress() {
+        let mut log = StatusLog::default();
+        log.push(StatusEvent::info(Operation::Grouping, "Grouped".into()));
+        log.finish(Operation::Grouping);
+        assert_eq!(log.events.len(), 1);
+        log.push(StatusEvent::progress(
+            Operation::Preview,
+            "Generating".into(),
+        ));
+        log.finish(Operation::Grouping);
+        assert_eq!(log.events.len(), 2);
+        log.finish(Operation::Preview);
+        assert_eq!(log.events.len(), 1);
+    }
+
+    #[test]
+    fn old_events_are_forgotten() {
+        let mut log = StatusLog::default();
+        for index in 0..MAX_EVENTS + 5 {
+            log.push(StatusEvent::info(Operation::Save, index.to_string()));
+        }
+        assert_eq!(log.events.len(), MAX_EVENTS);
+        assert_eq!(
+            log.events.front().map(|event| event.message.as_str()),
+            Some("5")
+        );
+    }
+}
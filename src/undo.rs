@@ -0,0 +1,193 @@
+//! Undo and redo for the changes the user makes by hand to which windows and
+//! tabs are exported, what the windows are called, the export options and the
+//! exclude rules, including changes from applied presets.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    exclude_rules::ExcludeRules,
+    host::{GenerateOptions, TabHistory, TabSort, WindowKey, WindowSort},
+    session::{NativeGroupId, TabId},
+    url_cleaning::UrlCleaning,
+};
+
+/// Number of changes that can be undone, older changes are forgotten.
+const MAX_STEPS: usize = 100;
+
+/// The part of [`GenerateOptions`] that can be undone together with the
+/// exclude rules. The grouping isn't included since the group indexes refer
+/// to the groups of the current grouping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditState {
+    open_group_indexes: Option<Vec<u32>>,
+    closed_group_indexes: Option<Vec<u32>>,
+    excluded_tabs: BTreeSet<TabId>,
+    excluded_native_groups: BTreeSet<NativeGroupId>,
+    window_names: BTreeMap<WindowKey, String>,
+    // Options that presets change:
+    table_of_content: bool,
+    tab_tree: bool,
+    container_labels: bool,
+    history: TabHistory,
+    url_cleaning: UrlCleaning,
+    window_sort: WindowSort,
+    tab_sort: TabSort,
+    exclude_rules: ExcludeRules,
+}
+impl EditState {
+    pub fn of(options: &GenerateOptions, exclude_rules: &ExcludeRules) -> Self {
+        Self {
+            open_group_indexes: options.open_group_indexes.clone(),
+            closed_group_indexes: options.closed_group_indexes.clone(),
+            excluded_tabs: options.excluded_tabs.clone(),
+            excluded_native_groups: options.excluded_native_groups.clone(),
+            window_names: options.window_names.clone(),
+            table_of_content: options.table_of_content,
+            tab_tree: options.tab_tree,
+            container_labels: options.container_labels,
+            history: options.history,
+            url_cleaning: options.url_cleaning,
+            window_sort: options.window_sort,
+            tab_sort: options.tab_sort,
+            exclude_rules: exclude_rules.clone(),
+        }
+    }
+
+    /// Overwrite the options and exclude rules with this state.
+    pub fn restore(self, options: &mut GenerateOptions, exclude_rules: &mut ExcludeRules) {
+        options.open_group_indexes = self.open_group_indexes;
+        options.closed_group_indexes = self.closed_group_indexes;
+        options.excluded_tabs = self.excluded_tabs;
+        options.excluded_native_groups = self.excluded_native_groups;
+        options.window_names = self.window_names;
+        options.table_of_content = self.table_of_content;
+        options.tab_tree = self.tab_tree;
+        options.container_labels = self.container_labels;
+        options.history = self.history;
+        options.url_cleaning = self.url_cleaning;
+        options.window_sort = self.window_sort;
+        options.tab_sort = self.tab_sort;
+        *exclude_rules = self.exclude_rules;
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct UndoHistory {
+    undo: Vec<EditState>,
+    redo: Vec<EditState>,
+}
+impl UndoHistory {
+    /// Remember the state from before a change, does nothing if the change
+    /// didn't modify anything.
+    pub fn record(&mut self, before: EditState, after: &EditState) {
+        if before == *after {
+            return;
+        }
+        if self.undo.len() >= MAX_STEPS {
+            self.undo.remove(0);
+        }
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    /// Forget all changes, for example because the indexes in them refer to
+    /// windows in another session.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// The state to restore to undo the latest change.
+    pub fn undo(&mut self, current: EditState) -> Option<EditState> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// The state to restore to redo the latest undone change.
+    pub fn redo(&mut self, current: EditState) -> Option<EditState> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(open: &[u32]) -> EditState {
+        let options = GenerateOptions {
+            open_group_indexes: Some(open.to_vec()),
+            ..GenerateOptions::default()
+        };
+        EditState::of(&options, &ExcludeRules::default())
+    }
+
+    #[test]
+    fn undo_and_redo_restore_states_in_order() {
+        let mut history = UndoHistory::default();
+        history.record(state(&[]), &state(&[0]));
+        history.record(state(&[0]), &state(&[0, 1]));
+        assert_eq!(history.undo(state(&[0, 1])), Some(state(&[0])));
+        assert_eq!(history.undo(state(&[0])), Some(state(&[])));
+        assert!(!history.can_undo());
+        assert_eq!(history.redo(state(&[])), Some(state(&[0])));
+        assert_eq!(history.redo(state(&[0])), Some(state(&[0, 1])));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn unchanged_states_are_not_recorded() {
+        let mut history = UndoHistory::default();
+        history.record(state(&[1]), &state(&[1]));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn new_changes_forget_undone_changes() {
+        let mut history = UndoHistory::default();
+        history.record(state(&[]), &state(&[0]));
+        history.undo(state(&[0]));
+        history.record(state(&[]), &state(&[1]));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn exclude_rules_are_part_of_the_state() {
+        let options = GenerateOptions::default();
+        let rules = ExcludeRules {
+            enabled: true,
+            ..ExcludeRules::default()
+        };
+        let before = EditState::of(&options, &ExcludeRules::default());
+        let after = EditState::of(&options, &rules);
+        assert_ne!(before, after);
+
+        let mut restored_options = GenerateOptions::default();
+        let mut restored_rules = ExcludeRules::default();
+        after.restore(&mut restored_options, &mut restored_rules);
+        assert_eq!(restored_rules, rules);
+    }
+
+    #[test]
+    fn old_changes_are_forgotten() {
+        let mut history = UndoHistory::default();
+        for index in 0..MAX_STEPS as u32 + 10 {
+            history.record(state(&[index]), &state(&[index + 1]));
+        }
+        let mut steps = 0;
+        while history.undo(state(&[])).is_some() {
+            steps += 1;
+        }
+        assert_eq!(steps, MAX_STEPS);
+    }
+}
@@ -0,0 +1,248 @@
+//! Normalize tab URLs before they are exported, for example by removing
+//! tracking parameters or unwrapping reader view and suspended tab URLs.
+
+use crate::session::SessionTabs;
+
+/// Query parameters that are only used to track where a visitor came from.
+const TRACKING_PARAMETERS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "igshid", "mc_cid",
+    "mc_eid", "_hsenc", "_hsmi", "mkt_tok",
+];
+
+/// Parameters that extensions which suspend tabs use to store the original
+/// URL, for example The Great Suspender uses `uri` and Auto Tab Discard uses
+/// `href`.
+const SUSPENDER_PARAMETERS: &[&str] = &["uri", "url", "href", "u"];
+
+/// Which changes are made to URLs before they are exported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct UrlCleaning {
+    /// Remove `utm_*`, `fbclid`, `gclid` and similar query parameters.
+    pub strip_tracking: bool,
+    /// Replace `about:reader?url=...` with the page shown in reader view.
+    pub unwrap_reader: bool,
+    /// Replace the pages of tab suspender extensions with the suspended page.
+    pub unwrap_suspended: bool,
+}
+
+impl UrlCleaning {
+    pub fn is_enabled(self) -> bool {
+        self.strip_tracking || self.unwrap_reader || self.unwrap_suspended
+    }
+
+    /// The cleaned URL, `None` if the URL isn't changed.
+    pub fn clean(self, url: &str) -> Option<String> {
+        let mut current = url.to_owned();
+        if self.unwrap_reader
+            && let Some(inner) = unwrap_reader(&current)
+        {
+            current = inner;
+        }
+        if self.unwrap_suspended
+            && let Some(inner) = unwrap_suspended(&current)
+        {
+            current = inner;
+        }
+        if self.strip_tracking
+            && let Some(stripped) = strip_tracking(&current)
+        {
+            current = stripped;
+        }
+        (current != url).then_some(current)
+    }
+}
+
+fn is_tracking_parameter(name: &str) -> bool {
+    name.starts_with("utm_") || TRACKING_PARAMETERS.contains(&name)
+}
+
+/// Remove tracking parameters from the query. The other parameters are kept
+/// exactly as they were written, since re-encoding them can change what some
+/// sites expect (for example `+` versus `%20`).
+fn strip_tracking(url: &str) -> Option<String> {
+    let mut parsed = url::Url::parse(url).ok()?;
+    let query = parsed.query()?;
+    let is_tracking = |segment: &&str| {
+        url::form_urlencoded::parse(segment.as_bytes())
+            .next()
+            .is_some_and(|(name, _)| is_tracking_parameter(&name))
+    };
+    if !query.split('&').any(|segment| is_tracking(&segment)) {
+        return None;
+    }
+    let kept = query
+        .split('&')
+        .filter(|segment| !is_tracking(segment))
+        .collect::<Vec<_>>()
+        .join("&");
+    parsed.set_query((!kept.is_empty()).then_some(kept.as_str()));
+    Some(parsed.into())
+}
+
+fn unwrap_reader(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("about:reader?")?;
+    url::form_urlencoded::parse(rest.as_bytes())
+        .find(|(name, _)| name == "url")
+        .map(|(_, value)| value.into_owned())
+}
+
+fn unwrap_suspended(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    if !matches!(parsed.scheme(), "moz-extension" | "chrome-extension") {
+        return None;
+    }
+    // Suspenders store the URL either in the query or in the fragment:
+    [parsed.query(), parsed.fragment()]
+        .into_iter()
+        .flatten()
+        .find_map(suspended_url)
+}
+
+/// Find the original URL in the parameters of a suspended tab's page. Some
+/// suspenders don't encode the URL, then it can contain `&` and everything
+/// after the parameter's name is part of it.
+fn suspended_url(params: &str) -> Option<String> {
+    let is_web_url = |url: &str| url.starts_with("http://") || url.starts_with("https://");
+    let mut start = 0;
+    for segment in params.split('&') {
+        let value_start = start;
+        start += segment.len() + 1;
+        let Some((name, _)) = segment.split_once('=') else {
+            continue;
+        };
+        if !SUSPENDER_PARAMETERS.contains(&name) {
+            continue;
+        }
+        let raw = &params[value_start + name.len() + 1..];
+        if is_web_url(raw) {
+            return Some(raw.to_owned());
+        }
+        let (_, value) = url::form_urlencoded::parse(segment.as_bytes()).next()?;
+        if is_web_url(&value) {
+            return Some(value.into_owned());
+        }
+    }
+    None
+}
+
+/// A tab whose URL is changed by the URL cleaning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlChange {
+    pub title: String,
+    pub original: String,
+    pub cleaned: String,
+}
+
+/// All URLs in a session that would be changed by the specified cleaning.
+pub fn preview(tabs: &SessionTabs, cleaning: UrlCleaning) -> Vec<UrlChange> {
+    tabs.open
+        .iter()
+        .chain(&tabs.closed)
+        .flat_map(|window| window.tabs.iter().chain(&window.closed_tabs))
+        .filter_map(|tab| {
+            Some(UrlChange {
+                cleaned: cleaning.clean(&tab.url)?,
+                title: tab.title.clone(),
+                original: tab.url.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: UrlCleaning = UrlCleaning {
+        strip_tracking: true,
+        unwrap_reader: true,
+        unwrap_suspended: true,
+    };
+
+    #[test]
+    fn strips_tracking_parameters() {
+        assert_eq!(
+            strip_tracking("https://a.com/p?utm_source=x&id=1&fbclid=abc#top").as_deref(),
+            Some("https://a.com/p?id=1#top")
+        );
+        assert_eq!(
+            strip_tracking("https://a.com/p?gclid=1&utm_medium=2").as_deref(),
+            Some("https://a.com/p")
+        );
+        assert_eq!(strip_tracking("https://a.com/p?id=1"), None);
+        assert_eq!(strip_tracking("https://a.com/p"), None);
+    }
+
+    #[test]
+    fn kept_parameters_are_not_reencoded() {
+        assert_eq!(
+            strip_tracking("https://a.com/?q=a+b%20c&x=%2F&&flag&utm_campaign=y").as_deref(),
+            Some("https://a.com/?q=a+b%20c&x=%2F&&flag")
+        );
+        assert_eq!(
+            strip_tracking("https://a.com/?utm_source=x&q=%C3%A5%3B").as_deref(),
+            Some("https://a.com/?q=%C3%A5%3B")
+        );
+    }
+
+    #[test]
+    fn unwraps_reader_view() {
+        assert_eq!(
+            unwrap_reader("about:reader?url=https%3A%2F%2Fa.com%2Fpost%3Fid%3D1").as_deref(),
+            Some("https://a.com/post?id=1")
+        );
+        assert_eq!(unwrap_reader("about:newtab"), None);
+    }
+
+    #[test]
+    fn unwraps_suspended_tabs() {
+        assert_eq!(
+            unwrap_suspended(
+                "chrome-extension://abc/suspended.html#ttl=Post&uri=https://a.com/post"
+            )
+            .as_deref(),
+            Some("https://a.com/post")
+        );
+        // Unencoded URLs keep their own parameters:
+        assert_eq!(
+            unwrap_suspended(
+                "chrome-extension://abc/suspended.html#ttl=X&uri=https://a.com/?x=1&y=2"
+            )
+            .as_deref(),
+            Some("https://a.com/?x=1&y=2")
+        );
+        assert_eq!(
+            unwrap_suspended(
+                "moz-extension://abc/discarded.html?href=https%3A%2F%2Fb.com%2F%3Fx%3D1%26y%3D2&t=1"
+            )
+            .as_deref(),
+            Some("https://b.com/?x=1&y=2")
+        );
+        assert_eq!(
+            unwrap_suspended("moz-extension://abc/discarded.html?href=https%3A%2F%2Fb.com%2F")
+                .as_deref(),
+            Some("https://b.com/")
+        );
+        // Only URLs of extension pages are unwrapped:
+        assert_eq!(unwrap_suspended("https://a.com/?url=https://b.com/"), None);
+        assert_eq!(
+            unwrap_suspended("moz-extension://abc/page.html?url=javascript:x"),
+            None
+        );
+    }
+
+    #[test]
+    fn clean_combines_all_steps() {
+        assert_eq!(
+            ALL.clean("about:reader?url=https%3A%2F%2Fa.com%2F%3Futm_source%3Dx%26id%3D1")
+                .as_deref(),
+            Some("https://a.com/?id=1")
+        );
+        assert_eq!(ALL.clean("https://a.com/?id=1"), None);
+        assert_eq!(
+            UrlCleaning::default().clean("https://a.com/?utm_source=x"),
+            None
+        );
+    }
+}